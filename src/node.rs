@@ -1,12 +1,22 @@
+#[allow(clippy::module_inception)]
 pub mod node;
 pub mod layer;
 pub mod tensor;
 pub mod edges;
 pub mod attribute;
+pub mod builder;
+pub mod cell;
+pub mod config;
 pub mod utils;
 pub mod domain;
+pub mod dot;
+pub mod function;
 pub mod scope;
+pub mod summary;
+pub mod kernel;
+pub mod mode;
 pub mod network;
 pub mod mesh;
+pub mod registry;
 
-pub mod processor;
\ No newline at end of file
+pub mod processor;
@@ -8,14 +8,21 @@
 use std::collections::HashMap;
 use std::hash::BuildHasherDefault;
 use rustc_hash::{FxHasher, FxHashMap};
+use crate::node::cell::Cell;
+use crate::node::kernel::Kernel;
 use crate::node::scope::Scope;
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Default)]
+#[serde(default)]
 pub struct Attribute {
     activation: Activation,
     cell_type: CellType,
+    #[serde(serialize_with = "sorted")]
     descriptor: FxHashMap<String, TensorDescriptor>,
     scope: Scope,
+    normalization: Normalization,
+    dropout: f64,
+    aggregation: Aggregation,
 }
 
 impl Attribute {
@@ -40,10 +47,63 @@ impl Attribute {
     /// ```
     pub fn new(activation: Activation, cell_type: CellType, descriptor: Vec<(&str, TensorDescriptor)>, scope: Scope) -> Attribute {
         Attribute { activation,
-            cell_type, descriptor: Attribute::build_descriptors(descriptor), scope,
+            cell_type, descriptor: Attribute::build_descriptors(descriptor), scope, normalization: Normalization::None, dropout: 0.0, aggregation: Aggregation::Concatenate,
         }
     }
 
+    /// Assigns a Normalization to the Attribute.
+    ///
+    /// Normalization is applied to a layer's values after its cell and before its activation.
+    ///
+    /// # Example(s)
+    /// ```
+    /// use anneml::node::attribute::{Activation, Attribute, CellType, Normalization, TensorDescriptor};
+    /// use anneml::node::scope::Scope;
+    ///
+    /// let descriptor = vec![("_SYSTEM_WEIGHTS", TensorDescriptor::RandN),("_SYSTEM_BIASES", TensorDescriptor::Const(0.0))];
+    /// let attribute = Attribute::new(Activation::TanH, CellType::Mlp, descriptor, Scope::new(0,1))
+    ///     .with_normalization(Normalization::Batch(0.1));
+    /// ```
+    pub fn with_normalization(mut self, normalization: Normalization) -> Attribute {
+        self.normalization = normalization;
+        self
+    }
+
+    /// Assigns a dropout rate to the Attribute.
+    ///
+    /// While passing in `Mode::Train`, each of the layer's values is dropped with probability `rate` after its activation,
+    /// the remaining values being scaled by 1 / (1 - `rate`). Dropout is disabled in `Mode::Eval`.
+    /// Masks are drawn from the Network's seeded random streams. A `rate` outside [0, 1) is rejected when the layer is built.
+    ///
+    /// # Example(s)
+    /// ```
+    /// use anneml::node::attribute::{Activation, Attribute, CellType, TensorDescriptor};
+    /// use anneml::node::scope::Scope;
+    ///
+    /// let descriptor = vec![("_SYSTEM_WEIGHTS", TensorDescriptor::RandN),("_SYSTEM_BIASES", TensorDescriptor::Const(0.0))];
+    /// let attribute = Attribute::new(Activation::TanH, CellType::Mlp, descriptor, Scope::new(0,1)).with_dropout(0.2);
+    /// assert_eq!(0.2, attribute.dropout());
+    /// ```
+    pub fn with_dropout(mut self, rate: f64) -> Attribute {
+        self.dropout = rate;
+        self
+    }
+
+    /// Assigns an Aggregation to the Attribute.
+    ///
+    /// The Aggregation of a vertex's output Attribute determines how the outputs of its children are combined.
+    ///
+    /// # Example(s)
+    /// ```
+    /// use anneml::node::attribute::{Aggregation, Attribute};
+    ///
+    /// let attribute = Attribute::default().with_aggregation(Aggregation::Mean);
+    /// ```
+    pub fn with_aggregation(mut self, aggregation: Aggregation) -> Attribute {
+        self.aggregation = aggregation;
+        self
+    }
+
     /// Maps a vector of (&str,TensorDescriptor) key value pairs to a hashmap.
     ///
     /// Descriptions are hashmaps that describe how to seed values in an individual layer.
@@ -58,6 +118,31 @@ impl Attribute {
         self.descriptor.get(key).unwrap()
     }
 
+    /// Acquire the TensorDescriptor associated with `key`, or `default` if the Attribute does not describe it.
+    pub(crate) fn description_or<'a>(&'a self, key: &str, default: &'a TensorDescriptor) -> &'a TensorDescriptor {
+        self.descriptor.get(key).unwrap_or(default)
+    }
+
+    /// Describes the parameters a layer with this Attribute requires but the Attribute leaves out with `descriptor`.
+    ///
+    /// Weights and biases are only required by layers whose Scope reaches other layers.
+    /// Custom cells declare their parameters when built, so they are left as they are.
+    pub(crate) fn with_default_descriptors(mut self, descriptor: &TensorDescriptor) -> Attribute {
+        let mut keys = vec![];
+        if self.scope.lower_bound() > 0 || self.scope.upper_bound() > 0 { keys.extend(["_SYSTEM_WEIGHTS", "_SYSTEM_BIASES"]); }
+        if self.cell_type.gates() > 0 { keys.extend(["_SYSTEM_GATE_WEIGHTS", "_SYSTEM_GATE_BIASES"]); }
+        match self.cell_type {
+            CellType::Conv1d(_) | CellType::Conv2d(_) => { keys.extend(["_SYSTEM_KERNELS", "_SYSTEM_KERNEL_BIASES"]); }
+            CellType::Attention(..) => { keys.extend(["_SYSTEM_ATTENTION_WEIGHTS", "_SYSTEM_ATTENTION_BIASES"]); }
+            CellType::Embedding(..) => { keys.push("_SYSTEM_EMBEDDINGS"); }
+            CellType::Rbf(_) => { keys.extend(["_SYSTEM_RBF_CENTERS", "_SYSTEM_RBF_WIDTHS"]); }
+            CellType::Som(..) => { keys.push("_SYSTEM_SOM_WEIGHTS"); }
+            _ => {}
+        }
+        keys.into_iter().for_each(|key| { self.descriptor.entry(key.to_string()).or_insert_with(|| descriptor.clone()); });
+        self
+    }
+
     /// Acquire an Activation reference associated with the Attribute.
     pub fn activation(&self) -> &Activation{
         &self.activation
@@ -72,44 +157,230 @@ impl Attribute {
     pub fn scope(&self) -> &Scope {
         &self.scope
     }
+
+    /// Acquire an Normalization reference associated with the Attribute.
+    pub fn normalization(&self) -> &Normalization {
+        &self.normalization
+    }
+
+    /// Acquire an Aggregation reference associated with the Attribute.
+    pub fn aggregation(&self) -> &Aggregation {
+        &self.aggregation
+    }
+
+    /// Acquire the dropout rate associated with the Attribute.
+    pub fn dropout(&self) -> f64 {
+        self.dropout
+    }
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Clone)]
+/// Serializes descriptors ordered by key, so written definitions are stable.
+fn sorted<S: serde::Serializer>(descriptor: &FxHashMap<String, TensorDescriptor>, serializer: S) -> Result<S::Ok, S::Error> {
+    serde::Serialize::serialize(&descriptor.iter().collect::<std::collections::BTreeMap<&String, &TensorDescriptor>>(), serializer)
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Default, Debug)]
 pub enum Activation{
+    #[default]
     Sigmoid,
     TanH,
     None,
 }
 
-impl Default for Activation {
-    fn default() -> Self {
-        Activation::Sigmoid
-    }
+/// Normalizes a layer's values between its cell and its activation.
+///
+/// Both normalizations keep a learnable scale and shift in the layer Tensor, starting at 1 and 0.
+/// `Batch(momentum)` normalizes with running statistics persisted in the layer Tensor. As every pass holds a single sample,
+/// the running mean and variance are updated with `momentum` on each pass in `Mode::Train`, and only read in `Mode::Eval`.
+/// `Layer` normalizes over the values of the layer itself.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Default, Debug)]
+pub enum Normalization {
+    #[default]
+    None,
+    Batch(f64),
+    Layer,
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Clone)]
+/// Defines how a vertex combines the outputs of its children.
+///
+/// `Concatenate` keeps every child output under its own key. All other modes combine the outputs element-wise into a single entry
+/// keyed by the vertex's UUID, each child output being the join of its entries, and every child output having the same size.
+/// `WeightedMean` weighs the children by `_SYSTEM_AGGREGATION_WEIGHTS` of the vertex's output Attribute, one weight per child with outputs,
+/// children being counted column by column. The weights default to 1, and are rebuilt whenever a child is added.
+/// `Vote` treats each unit as a class: every child votes for its largest unit and the output is one-hot on the majority class,
+/// ties going to the lowest class.
+/// The Aggregation is only read by vertices of `VertexKind::Aggregate`.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Default, PartialEq, Debug)]
+pub enum Aggregation {
+    #[default]
+    Concatenate,
+    Sum,
+    Mean,
+    WeightedMean,
+    Max,
+    Vote,
+}
+
+/// Defines how a layer transforms the values it receives.
+///
+/// Recurrent cells (`Lstm`, `Gru`) additionally require `_SYSTEM_GATE_WEIGHTS` and `_SYSTEM_GATE_BIASES` descriptors.
+/// Gate weights are of size (n, 2n, gates) and gate biases (n, 1, gates), n being the layer size.
+/// Their hidden (and cell) state is carried across successive passes until the Node is reset.
+///
+/// Convolution cells (`Conv1d`, `Conv2d`) read the output of the previous layer instead of weighted values, so they may not be the input layer.
+/// They require `_SYSTEM_KERNELS` of size (kernel rows, kernel columns, input channels, output channels) and `_SYSTEM_KERNEL_BIASES` of size (1, output channels).
+/// Their layer size is computed from the previous layer and the Kernel.
+///
+/// Pooling cells (`MaxPool`, `AvgPool`) reduce every window of the Kernel within each channel, their Kernel having equal input and output channels.
+/// Padding is left out of each window, so it never wins a maximum or dilutes an average.
+/// Global pooling cells reduce each of the given number of channels to a single value.
+/// `Flatten` passes the output of the previous layer through unchanged, as layer values are always stored flat with one channel after the other.
+/// Like convolutions, pooling and flatten layers read the previous layer and have their size computed from it.
+///
+/// `Attention(dimension, heads)` reads the previous layer as a sequence of tokens of size `dimension` and applies multi-head self-attention.
+/// It requires `_SYSTEM_ATTENTION_WEIGHTS` of size (dimension, dimension, 4) and `_SYSTEM_ATTENTION_BIASES` of size (1, dimension, 4),
+/// holding the query, key, value and output projections in that order. Its layer size equals the size of the previous layer.
+///
+/// `Embedding(key, vocabulary, dimension)` reads integer indices from the input Tensor entry `key` rather than from a layer,
+/// and looks up one row of `_SYSTEM_EMBEDDINGS`, of size (vocabulary, dimension), for each of them.
+/// Its layer size is the number of indices times `dimension`, the rows being placed one after the other.
+/// The indices still enter the input layer, so an embedding usually directly follows the input layer within its Scope.
+///
+/// `Rbf(centers)` applies a gaussian radial basis function for each center to the output of the previous layer.
+/// It requires `_SYSTEM_RBF_CENTERS` of size (centers, previous layer size) and `_SYSTEM_RBF_WIDTHS` of size (centers, 1).
+/// Explicit widths (`Values`, `File`) must be positive, other descriptors are built as their absolute value, and a zero width fails the build.
+/// Centers and widths are learned through `Node::train`.
+///
+/// `Som(rows, columns, rate, radius)` is a Kohonen self-organizing map whose units compete for the output of the previous layer.
+/// It requires `_SYSTEM_SOM_WEIGHTS` of size (rows * columns, previous layer size), and outputs a one-hot row of the winning unit.
+/// While passing in `Mode::Train` (including `Node::train`) the units are updated towards the input, with learning `rate` and neighbourhood `radius` on the grid.
+///
+/// `Custom(name, configuration)` is a Cell implemented outside of the crate, rebuilt by the constructor registered under `name`.
+/// See `CellType::custom` and `register_cell`.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Default, Debug)]
 pub enum CellType {
+    #[default]
     Mlp,
+    Lstm,
+    Gru,
+    Conv1d(Kernel),
+    Conv2d(Kernel),
+    MaxPool(Kernel),
+    AvgPool(Kernel),
+    GlobalMaxPool(usize),
+    GlobalAvgPool(usize),
+    Flatten,
+    Attention(usize, usize),
+    Embedding(String, usize, usize),
+    Rbf(usize),
+    Som(usize, usize, f64, f64),
+    Custom(String, String),
 }
 
-impl Default for CellType {
-    fn default() -> Self {
-        CellType::Mlp
+impl CellType {
+    /// Creates the CellType of a custom Cell, recording its name and configuration.
+    ///
+    /// The Cell's constructor has to be registered under its name before the Node is built.
+    ///
+    /// # Example(s)
+    /// ```
+    /// use arrayfire::Array;
+    /// use anneml::node::attribute::CellType;
+    /// use anneml::node::cell::Cell;
+    /// use anneml::node::mode::Mode;
+    /// use anneml::node::tensor::Tensor;
+    ///
+    /// struct Double;
+    ///
+    /// impl Cell for Double {
+    ///     fn name(&self) -> &str { "double" }
+    ///     fn forward(&self, _tensor: &mut Tensor, input: Array<f64>, _mode: Mode) -> Array<f64> { input * 2.0 }
+    /// }
+    ///
+    /// let cell_type = CellType::custom(&Double);
+    /// assert!(matches!(cell_type, CellType::Custom(name, _) if name == "double"));
+    /// ```
+    pub fn custom(cell: &dyn Cell) -> CellType {
+        CellType::Custom(cell.name().to_string(), cell.configuration())
+    }
+
+    /// Number of gates stored in the layer Tensor of a recurrent cell.
+    pub(crate) fn gates(&self) -> u64 {
+        match self {
+            CellType::Lstm => { 4 }
+            CellType::Gru => { 3 }
+            _ => { 0 }
+        }
+    }
+
+    /// Returns whether the cell accumulates weighted values from the layers within Scope.
+    ///
+    /// Cells that are not dense transform the output of the previous layer directly.
+    /// Custom cells are asked through their layer, see `Layer::is_dense`.
+    pub(crate) fn is_dense(&self) -> bool {
+        matches!(self, CellType::Mlp | CellType::Lstm | CellType::Gru)
+    }
+
+    /// Returns the layer size produced by the cell given the size of its source, if the cell determines its own size.
+    ///
+    /// The source is the previous layer, or the input Tensor entry of the cell's `input_key`.
+    /// Custom cells are asked through their layer, see `Layer::output_width`.
+    pub(crate) fn output_width(&self, width: usize) -> Result<Option<usize>, &'static str> {
+        match self {
+            CellType::Conv1d(kernel) | CellType::Conv2d(kernel) => { Ok(Some(kernel.output_width(width)?)) }
+            CellType::MaxPool(kernel) | CellType::AvgPool(kernel) => { Ok(Some(kernel.pool_width(width)?)) }
+            CellType::GlobalMaxPool(channels) | CellType::GlobalAvgPool(channels) => {
+                if *channels == 0 || !width.is_multiple_of(*channels) { return Err("Layer size is not divisible into pooling channels.") }
+                Ok(Some(*channels))
+            }
+            CellType::Flatten => { Ok(Some(width)) }
+            CellType::Attention(dimension, heads) => {
+                if *dimension == 0 || !width.is_multiple_of(*dimension) { return Err("Layer size is not divisible into attention tokens.") }
+                if *heads == 0 || !dimension.is_multiple_of(*heads) { return Err("Attention dimension is not divisible into heads.") }
+                Ok(Some(width))
+            }
+            CellType::Embedding(_, vocabulary, dimension) => {
+                if *vocabulary == 0 || *dimension == 0 { return Err("Embedding vocabulary and dimension must not be empty.") }
+                Ok(Some(width * dimension))
+            }
+            CellType::Rbf(centers) => {
+                if *centers == 0 { return Err("Radial basis function cell must have centers.") }
+                Ok(Some(*centers))
+            }
+            CellType::Som(rows, columns, rate, radius) => {
+                if rows * columns == 0 { return Err("Self-organizing map grid must not be empty.") }
+                if *rate < 0.0 || *radius <= 0.0 { return Err("Self-organizing map rate must not be negative and radius must be positive.") }
+                Ok(Some(rows * columns))
+            }
+            _ => { Ok(None) }
+        }
+    }
+
+    /// Returns the key of the input Tensor entry read by the cell, if it does not read a layer.
+    pub(crate) fn input_key(&self) -> Option<&str> {
+        match self {
+            CellType::Embedding(key, _, _) => { Some(key) }
+            _ => { None }
+        }
     }
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Clone)]
+/// Describes how the values of a layer array are seeded.
+///
+/// `Values` and `File` seed a single connection block explicitly. A block has one row for each unit of the target layer and
+/// one column for each unit of the source layer (biases have a single column). Values are read in column-major order, matching Arrayfire.
+/// `File` accepts a `.npy` file (`<f8` or `<f4`) or a comma separated file with one matrix row per line.
+/// Explicit values are validated against the dimensions computed from the layer topology when the Node is built,
+/// and require the layer's Scope to connect it to a single other layer: Scopes spanning several connection blocks fail the build.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Default)]
 pub enum TensorDescriptor {
+    #[default]
     RandN,
     RandU,
     RangeN(f64),
     RangeU(f64),
     Range(f64),
     Const(f64),
+    Values(Vec<f64>, Vec<u64>),
+    File(String),
 }
-
-impl Default for TensorDescriptor {
-    fn default() -> Self {
-        TensorDescriptor::RandN
-    }
-}
\ No newline at end of file
@@ -0,0 +1,211 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use uuid::Uuid;
+use crate::node::attribute::Attribute;
+use crate::node::edges::NodeRange;
+use crate::node::function::Function;
+use crate::node::node::{Node, NodeType, VertexKind};
+
+/// Fluent description of a Node and its descendants.
+///
+/// Every Node is named, and peers are linked by name. UUIDs are generated unless assigned with `NodeBuilder::uuid`.
+/// Nothing is validated until `NodeBuilder::build`.
+///
+/// # Example(s)
+/// ```
+/// use anneml::node::attribute::Attribute;
+/// use anneml::node::builder::NodeBuilder;
+/// use anneml::node::edges::NodeRange;
+/// use anneml::node::network::Network;
+///
+/// let attribute = Attribute::default();
+/// let root = NodeBuilder::vertex("root", attribute.clone(), attribute.clone())
+///     .child(NodeBuilder::leaf("encoder", attribute.clone()).layer(attribute.clone(), 3).layer(attribute.clone(), 2))
+///     .child(NodeBuilder::leaf("decoder", attribute.clone()).layer(attribute.clone(), 2)
+///         .inputs_from_peer("encoder", NodeRange::All)
+///         .outputs_to_parent(NodeRange::All))
+///     .build()
+///     .unwrap();
+///
+/// let network = Network::new(root);
+/// assert!(network.find_by_path("root/decoder").is_some());
+/// ```
+pub struct NodeBuilder {
+    name: Option<String>,
+    uuid: Option<Uuid>,
+    kind: Kind,
+    input_from_parent: Option<NodeRange>,
+    output_to_parent: Option<NodeRange>,
+    peers: Vec<(String, NodeRange)>,
+    misuse: Option<&'static str>,
+}
+
+enum Kind {
+    Leaf(Attribute, Vec<(Attribute, u64)>),
+    Vertex(VertexKind, Attribute, Attribute, Vec<Vec<NodeBuilder>>),
+    Function(String, String),
+}
+
+impl NodeBuilder {
+    /// Describes a leaf with the Attribute of its input layer. Hidden layers are added with `NodeBuilder::layer`.
+    pub fn leaf(name: &str, input: Attribute) -> Self {
+        NodeBuilder::new(name, Kind::Leaf(input, vec![]))
+    }
+
+    /// Describes a vertex with the Attributes of its input and output layers. Children are added with `NodeBuilder::child`.
+    ///
+    /// The vertex aggregates its children unless given another kind with `NodeBuilder::vertex_kind`.
+    pub fn vertex(name: &str, input: Attribute, output: Attribute) -> Self {
+        NodeBuilder::new(name, Kind::Vertex(VertexKind::Aggregate, input, output, vec![]))
+    }
+
+    /// Describes a function Node wrapping `function`. See `NodeType::function`.
+    pub fn function(name: &str, function: &dyn Function) -> Self {
+        NodeBuilder::new(name, Kind::Function(function.name().to_string(), function.configuration()))
+    }
+
+    /// Describes a function Node wrapping the Function registered under `function`.
+    pub(crate) fn registered_function(name: &str, function: &str, configuration: &str) -> Self {
+        NodeBuilder::new(name, Kind::Function(function.to_string(), configuration.to_string()))
+    }
+
+    fn new(name: &str, kind: Kind) -> Self {
+        NodeBuilder { name: Some(name.to_string()), uuid: None, kind, input_from_parent: None, output_to_parent: None, peers: vec![], misuse: None }
+    }
+
+    /// Leaves the Node unnamed. Peers then link to it by UUID, which has to be assigned.
+    pub(crate) fn unnamed(mut self) -> Self {
+        self.name = None;
+        self
+    }
+
+    /// Assigns the UUID of the Node instead of generating one.
+    pub fn uuid(mut self, uuid: Uuid) -> Self {
+        self.uuid = Some(uuid);
+        self
+    }
+
+    /// Appends a hidden layer of `count` values to a leaf.
+    pub fn layer(mut self, attribute: Attribute, count: u64) -> Self {
+        match &mut self.kind {
+            Kind::Leaf(_, hidden) => { hidden.push((attribute, count)); }
+            _ => { self.misuse.get_or_insert("Only a leaf has layers."); }
+        }
+        self
+    }
+
+    /// Assigns how a vertex traverses its children.
+    pub fn vertex_kind(mut self, vertex_kind: VertexKind) -> Self {
+        match &mut self.kind {
+            Kind::Vertex(kind, _, _, _) => { *kind = vertex_kind; }
+            _ => { self.misuse.get_or_insert("Only a vertex has a kind."); }
+        }
+        self
+    }
+
+    /// Places a child of a vertex in a new column.
+    pub fn child(mut self, child: NodeBuilder) -> Self {
+        match &mut self.kind {
+            Kind::Vertex(_, _, _, columns) => { columns.push(vec![child]); }
+            _ => { self.misuse.get_or_insert("Only a vertex has children."); }
+        }
+        self
+    }
+
+    /// Places a child of a vertex in the next row of `column`, creating the column if needed.
+    pub fn child_in(mut self, column: usize, child: NodeBuilder) -> Self {
+        match &mut self.kind {
+            Kind::Vertex(_, _, _, columns) => {
+                if columns.len() <= column { columns.resize_with(column + 1, Vec::new); }
+                columns[column].push(child);
+            }
+            _ => { self.misuse.get_or_insert("Only a vertex has children."); }
+        }
+        self
+    }
+
+    /// Restricts the inputs the Node receives from its parent.
+    pub fn inputs_from_parent(mut self, node_range: NodeRange) -> Self {
+        self.input_from_parent = Some(node_range);
+        self
+    }
+
+    /// Restricts the outputs the Node passes to its parent.
+    pub fn outputs_to_parent(mut self, node_range: NodeRange) -> Self {
+        self.output_to_parent = Some(node_range);
+        self
+    }
+
+    /// Links the outputs of the sibling named `peer` to the inputs of the Node.
+    pub fn inputs_from_peer(mut self, peer: &str, node_range: NodeRange) -> Self {
+        self.peers.push((peer.to_string(), node_range));
+        self
+    }
+
+    /// Builds the described Node and its descendants.
+    ///
+    /// # Errors
+    /// Returns an Err if:
+    /// 1) A leaf has no hidden layer, a vertex has no child, or a vertex has an empty child column.
+    /// 2) Two children of a vertex share a name.
+    /// 3) A peer link names no sibling or is declared on the root Node, or a link is rejected by `Node::link_peer`,
+    ///    `Node::link_input_from_parent` or `Node::link_output_to_parent`.
+    /// 4) Layers were added to a Node that is not a leaf, or children or a VertexKind to a Node that is not a vertex.
+    pub fn build(self) -> Result<Node, &'static str> {
+        if !self.peers.is_empty() { return Err("Peer links require a parent.") }
+        self.build_node()
+    }
+
+    fn build_node(self) -> Result<Node, &'static str> {
+        if let Some(misuse) = self.misuse { return Err(misuse) }
+        let uuid = self.uuid.unwrap_or_else(Uuid::new_v4);
+        let node = match self.kind {
+            Kind::Leaf(input, hidden) => {
+                if hidden.is_empty() { return Err("Leaf requires a hidden layer.") }
+                Node::new(uuid, NodeType::Leaf(input, hidden))
+            }
+            Kind::Function(name, configuration) => { Node::new(uuid, NodeType::Function(name, configuration)) }
+            Kind::Vertex(kind, input, output, columns) => { NodeBuilder::build_vertex(uuid, kind, input, output, columns)? }
+        };
+        if let Some(node_range) = self.input_from_parent { node.link_input_from_parent(node_range)?; }
+        if let Some(node_range) = self.output_to_parent { node.link_output_to_parent(node_range)?; }
+        Ok(match self.name {
+            Some(name) => { node.with_name(&name) }
+            None => { node }
+        })
+    }
+
+    /// Builds the children of a vertex column by column, then links them to their peers with `Node::link_peer`.
+    fn build_vertex(uuid: Uuid, kind: VertexKind, input: Attribute, output: Attribute, columns: Vec<Vec<NodeBuilder>>) -> Result<Node, &'static str> {
+        if columns.is_empty() { return Err("Vertex requires a child.") }
+        if columns.iter().any(|column| column.is_empty()) { return Err("Vertex has an empty child column.") }
+        let positions = columns.iter().enumerate()
+            .flat_map(|(x, column)| column.iter().enumerate().map(move |(y, child)| (child.name.clone(), child.uuid, (x, y)))).collect::<Vec<(Option<String>, Option<Uuid>, (usize, usize))>>();
+        if positions.iter().enumerate().any(|(i, (name, _, _))| name.is_some() && positions[..i].iter().any(|(other, _, _)| other == name)) { return Err("Siblings must have unique names.") }
+
+        let mut vertex: Option<Node> = None;
+        let mut links = vec![];
+        for (x, column) in columns.into_iter().enumerate() {
+            for (y, mut child) in column.into_iter().enumerate() {
+                for (peer, node_range) in std::mem::take(&mut child.peers) {
+                    let (_, _, position) = positions.iter().find(|(name, uuid, _)| name.as_deref() == Some(peer.as_str()) || uuid.map(|uuid| uuid.to_string()) == Some(peer.clone()))
+                        .ok_or("Peer is not a sibling.")?;
+                    links.push((*position, (x, y), node_range));
+                }
+                let child = child.build_node()?;
+                match vertex.as_mut() {
+                    None => { vertex = Some(Node::new(uuid, NodeType::Vertex(kind.clone(), input.clone(), child, output.clone()))); }
+                    Some(vertex) => { vertex.add_child_to_parent(child, if y == 0 { None } else { Some(x) })?; }
+                }
+            }
+        }
+        let vertex = vertex.unwrap();
+        for (from, to, node_range) in links { vertex.link_peer(from, to, node_range)?; }
+        Ok(vertex)
+    }
+}
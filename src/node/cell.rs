@@ -0,0 +1,96 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use std::sync::Arc;
+use arrayfire::{Array, Dim4};
+use crate::node::mode::Mode;
+use crate::node::registry::Registry;
+use crate::node::tensor::Tensor;
+
+/// Constructs a Cell from the configuration it recorded when it was saved.
+pub type CellConstructor = fn(&str) -> Result<Box<dyn Cell>, &'static str>;
+
+static REGISTRY: Registry<dyn Cell> = Registry::new("Cell is not registered.");
+
+/// A layer transformation that is implemented outside of the crate.
+///
+/// Cells are used through `CellType::Custom`, which only records the Cell's name and configuration.
+/// The Cell is rebuilt from them by the constructor registered under its name, so Networks saved with custom cells load
+/// wherever the same constructors are registered.
+/// Parameters and state are stored in the layer Tensor under the keys the Cell declares, and are saved along with the Network.
+/// Like the keys of built-in cells, these keys have to contain `_SYSTEM` so they are not counted towards the layer size.
+pub trait Cell: Send + Sync {
+    /// Name the Cell's constructor is registered under.
+    fn name(&self) -> &str;
+
+    /// Configuration handed back to the constructor when the Cell is rebuilt.
+    fn configuration(&self) -> String { String::new() }
+
+    /// Returns whether the Cell accumulates weighted values from the layers within Scope, or else transforms the output of the previous layer.
+    fn is_dense(&self) -> bool { false }
+
+    /// Returns the layer size produced by the Cell given the size of the previous layer, if the Cell determines its own size.
+    ///
+    /// # Errors
+    /// Returns an Err if the Cell can not be applied to the previous layer.
+    fn output_width(&self, _width: usize) -> Result<Option<usize>, &'static str> { Ok(None) }
+
+    /// Declares the parameters of the Cell and their dimensions, given the size of the layer and of the previous layer.
+    ///
+    /// Parameters are built from the TensorDescriptor of the same key in the layer's Attribute.
+    fn parameters(&self, _width: usize, _previous: usize) -> Vec<(String, Dim4)> { vec![] }
+
+    /// Declares the state carried across passes and its dimensions, given the size of the layer and of the previous layer.
+    ///
+    /// State starts at zero, and is cleared when the Node's state is reset.
+    fn state(&self, _width: usize, _previous: usize) -> Vec<(String, Dim4)> { vec![] }
+
+    /// Transforms the input of the layer, with access to the layer's parameters and state.
+    fn forward(&self, tensor: &mut Tensor, input: Array<f64>, mode: Mode) -> Array<f64>;
+
+    /// Propagates the gradient of the layer output back to its input, descending the Cell's parameters by `rate`.
+    ///
+    /// Called by `Node::train` with the input and the output gradient of the layer's last pass.
+    ///
+    /// # Errors
+    /// Returns an Err if the Cell does not implement backward.
+    fn backward(&self, _tensor: &mut Tensor, _input: &Array<f64>, _gradient: &Array<f64>, _rate: f64) -> Result<Array<f64>, &'static str> {
+        Err("Cell does not implement backward.")
+    }
+}
+
+/// Registers a Cell constructor by name, replacing any constructor previously registered under that name.
+///
+/// # Example(s)
+/// ```
+/// use arrayfire::Array;
+/// use anneml::node::attribute::CellType;
+/// use anneml::node::cell::{Cell, register_cell};
+/// use anneml::node::mode::Mode;
+/// use anneml::node::tensor::Tensor;
+///
+/// struct Identity;
+///
+/// impl Cell for Identity {
+///     fn name(&self) -> &str { "identity" }
+///     fn forward(&self, _tensor: &mut Tensor, input: Array<f64>, _mode: Mode) -> Array<f64> { input }
+/// }
+///
+/// register_cell("identity", |_| Ok(Box::new(Identity)));
+/// let cell_type = CellType::custom(&Identity);
+/// ```
+pub fn register_cell(name: &str, constructor: CellConstructor) {
+    REGISTRY.register(name, constructor);
+}
+
+/// Rebuilds a registered Cell from its name and configuration.
+///
+/// # Errors
+/// Returns an Err if no constructor is registered under `name`, or if the constructor rejects the configuration.
+pub(crate) fn resolve_cell(name: &str, configuration: &str) -> Result<Arc<dyn Cell>, &'static str> {
+    REGISTRY.resolve(name, configuration)
+}
@@ -0,0 +1,136 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use uuid::Uuid;
+use crate::node::attribute::{Attribute, TensorDescriptor};
+use crate::node::builder::NodeBuilder;
+use crate::node::edges::NodeRange;
+use crate::node::node::{Node, VertexKind};
+
+/// Architecture of a Network, as written in a TOML definition file. Weights are not part of the definition.
+///
+/// The root table holds an optional `seed` and the `root` Node.
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct NetworkConfig {
+    #[serde(default)]
+    pub(crate) seed: u64,
+    pub(crate) root: NodeConfig,
+}
+
+/// Definition of a Node.
+///
+/// A Node with a `function` table is a function Node, a Node with `columns` of children a vertex, and any other Node a leaf.
+/// The `kind` of a vertex defaults to `Aggregate`.
+/// Peers are referred to by name, or by UUID if they have none.
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct NodeConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    uuid: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    inputs_from_parent: Option<NodeRange>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    outputs_to_parent: Option<NodeRange>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    inputs_from_peers: Vec<PeerConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    function: Option<FunctionConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    kind: Option<VertexKind>,
+    #[serde(default)]
+    input: Attribute,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    output: Option<Attribute>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    layers: Vec<LayerConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    columns: Vec<ColumnConfig>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct PeerConfig {
+    peer: String,
+    range: NodeRange,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct FunctionConfig {
+    name: String,
+    #[serde(default)]
+    configuration: String,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct LayerConfig {
+    count: u64,
+    #[serde(default)]
+    attribute: Attribute,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ColumnConfig {
+    children: Vec<NodeConfig>,
+}
+
+impl NodeConfig {
+    /// Describes the architecture of the Node and its descendants.
+    pub(crate) fn new(node: &Node) -> Self {
+        let mesh = node.mesh();
+        let edges = mesh.edges();
+        let layers = mesh.layers().iter().map(|layer| layer.lock().unwrap()).collect::<Vec<_>>();
+        let uuid = node.uuid().to_string();
+        let peers = node.children().iter().map(|column| column.iter().map(|child| {
+            let child = child.lock().unwrap();
+            child.name().map(str::to_string).unwrap_or_else(|| child.uuid().to_string())
+        }).collect()).collect::<Vec<Vec<String>>>();
+        NodeConfig {
+            name: node.name().map(str::to_string),
+            uuid: Some(node.uuid()),
+            inputs_from_parent: Some(edges.input_from_parent.clone()),
+            outputs_to_parent: Some(edges.output_to_parent.clone()),
+            inputs_from_peers: vec![],
+            function: node.function_name().map(|name| FunctionConfig { name: name.to_string(), configuration: node.function_configuration().unwrap().to_string() }),
+            kind: node.vertex_kind().cloned(),
+            input: layers[0].attribute.clone(),
+            output: if node.is_leaf_node() || node.is_function_node() { None } else { Some(layers[1].attribute.clone()) },
+            layers: if node.is_leaf_node() {
+                layers[1..].iter().map(|layer| LayerConfig { count: layer.tensor.get(&uuid).map(|value| value.dims()[1]).unwrap_or(0), attribute: layer.attribute.clone() }).collect()
+            } else { vec![] },
+            columns: node.children().iter().map(|column| ColumnConfig { children: column.iter().map(|child| {
+                let child = child.lock().unwrap();
+                let mut config = NodeConfig::new(&child);
+                config.inputs_from_peers = child.mesh().edges().input_from_peer_output.iter().map(|(position, range)| PeerConfig { peer: peers[position.0][position.1].clone(), range: range.clone() }).collect();
+                config
+            }).collect() }).collect(),
+        }
+    }
+
+    /// Describes the Node as a NodeBuilder, validated when built.
+    ///
+    /// Parameters the layers of a leaf require but their Attributes do not describe are seeded with `RandN`.
+    pub(crate) fn builder(self) -> NodeBuilder {
+        let name = self.name.clone().unwrap_or_default();
+        let mut builder = match (self.function, self.columns.is_empty()) {
+            (Some(function), _) => { NodeBuilder::registered_function(&name, &function.name, &function.configuration) }
+            (None, true) => {
+                let leaf = NodeBuilder::leaf(&name, self.input.with_default_descriptors(&TensorDescriptor::RandN));
+                self.layers.into_iter().fold(leaf, |builder, layer| builder.layer(layer.attribute.with_default_descriptors(&TensorDescriptor::RandN), layer.count))
+            }
+            (None, false) => {
+                let vertex = NodeBuilder::vertex(&name, self.input, self.output.unwrap_or_default()).vertex_kind(self.kind.unwrap_or_default());
+                self.columns.into_iter().enumerate().fold(vertex, |vertex, (column, children)|
+                    children.children.into_iter().fold(vertex, |vertex, child| vertex.child_in(column, child.builder())))
+            }
+        };
+        if self.name.is_none() { builder = builder.unnamed(); }
+        if let Some(uuid) = self.uuid { builder = builder.uuid(uuid); }
+        if let Some(node_range) = self.inputs_from_parent { builder = builder.inputs_from_parent(node_range); }
+        if let Some(node_range) = self.outputs_to_parent { builder = builder.outputs_to_parent(node_range); }
+        self.inputs_from_peers.into_iter().fold(builder, |builder, peer| builder.inputs_from_peer(&peer.peer, peer.range))
+    }
+}
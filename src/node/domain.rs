@@ -32,21 +32,12 @@ impl Domain {
         }
     }
 
-    pub(crate) fn underflow(&self) -> usize {
-        self.underflow
-    }
     pub(crate) fn start(&self) -> usize {
         self.start
     }
-    pub(crate) fn index(&self) -> usize {
-        self.index
-    }
     pub(crate) fn end(&self) -> usize {
         self.end
     }
-    pub(crate) fn overflow(&self) -> usize {
-        self.overflow
-    }
     pub(crate) fn domain_data(&self) -> (usize, usize, usize, usize, usize) {
         (self.underflow, self.start, self.index, self.end, self.overflow)
     }
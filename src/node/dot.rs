@@ -0,0 +1,99 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use std::fmt::Write;
+use crate::node::domain::Domain;
+use crate::node::edges::NodeRange;
+use crate::node::node::{Node, VertexKind};
+
+/// Renders the Node and its descendants as a Graphviz DOT digraph.
+///
+/// Each Node is labeled with its name, UUID and (column, row) position in its parent.
+/// Parent input links are solid, output links dashed and peer links dotted, each labeled with its NodeRange.
+/// If `layers` is true, leaves are drawn as clusters of their layers, linked by the connections their Scopes span.
+pub(crate) fn graph(node: &Node, layers: bool) -> String {
+    let mut dot = String::from("digraph anneml {\n    node [shape=box];\n");
+    write_node(&mut dot, node, None, layers);
+    dot.push_str("}\n");
+    dot
+}
+
+/// Writes the Node, its children and the links between them.
+fn write_node(dot: &mut String, node: &Node, position: Option<(usize, usize)>, layers: bool) {
+    let uuid = node.uuid();
+    let mut title = match node.name() {
+        Some(name) => { format!("{}\\n{}", escape(name), uuid) }
+        None => { uuid.to_string() }
+    };
+    if let Some((column, row)) = position { title.push_str(&format!("\\n({}, {})", column, row)); }
+    let mesh = node.mesh();
+
+    if node.is_function_node() {
+        writeln!(dot, "    \"{}\" [label=\"{}\\nfunction: {}\"];", uuid, title, escape(node.function_name().unwrap())).unwrap();
+    } else if node.is_leaf_node() && layers {
+        let topology = mesh.topology();
+        writeln!(dot, "    subgraph \"cluster_{}\" {{\n        label=\"{}\\nleaf\";", uuid, title).unwrap();
+        mesh.layers().iter().enumerate().for_each(|(index, layer)| {
+            let layer = layer.lock().unwrap();
+            let cell_type = escape(&format!("{:?}", layer.attribute.cell_type()));
+            writeln!(dot, "        \"{}:{}\" [label=\"{}: {} {} {:?}\"];", uuid, index, index, topology[index], cell_type, layer.attribute.activation()).unwrap();
+        });
+        dot.push_str("    }\n");
+        let dense = mesh.layers().iter().map(|layer| layer.lock().unwrap().is_dense()).collect::<Vec<bool>>();
+        mesh.layers().iter().enumerate().for_each(|(index, layer)| {
+            let domain = Domain::new(layer.lock().unwrap().attribute.scope(), index, topology.len() as isize);
+            (domain.start()..=domain.end()).filter(|target| *target != index && dense[*target]).for_each(|target| {
+                let style = if target == index + 1 { "" } else { " [style=dashed, label=\"skip\"]" };
+                writeln!(dot, "    \"{}:{}\" -> \"{}:{}\"{};", uuid, index, uuid, target, style).unwrap();
+            });
+        });
+    } else if node.is_leaf_node() {
+        writeln!(dot, "    \"{}\" [label=\"{}\\nleaf {:?}\"];", uuid, title, mesh.topology()).unwrap();
+    } else {
+        let kind = match node.vertex_kind().cloned().unwrap_or_default() {
+            VertexKind::Aggregate => { format!("{:?}", mesh.layers()[1].lock().unwrap().attribute.aggregation()) }
+            kind => { format!("{:?}", kind) }
+        };
+        writeln!(dot, "    \"{}\" [label=\"{}\\nvertex {}\", shape=ellipse];", uuid, title, kind).unwrap();
+        let exits = node.children().iter().map(|column| column.iter().map(|child| exit(&child.lock().unwrap(), layers)).collect()).collect::<Vec<Vec<String>>>();
+        node.children().iter().enumerate().for_each(|(column, children)| children.iter().enumerate().for_each(|(row, child)| {
+            let child = child.lock().unwrap();
+            write_node(dot, &child, Some((column, row)), layers);
+            let mesh = child.mesh();
+            let edges = mesh.edges();
+            let entry = entry(&child, layers);
+            writeln!(dot, "    \"{}\" -> {} [label=\"{}\"];", uuid, entry, range(&edges.input_from_parent)).unwrap();
+            writeln!(dot, "    {} -> \"{}\" [label=\"{}\", style=dashed];", exits[column][row], uuid, range(&edges.output_to_parent)).unwrap();
+            edges.input_from_peer_output.iter().for_each(|((x, y), node_range)| {
+                writeln!(dot, "    {} -> {} [label=\"{}\", style=dotted];", exits[*x][*y], entry, range(node_range)).unwrap();
+            });
+        }));
+    }
+}
+
+/// Returns the DOT identifier receiving the inputs of the Node.
+fn entry(node: &Node, layers: bool) -> String {
+    if node.is_leaf_node() && layers { format!("\"{}:0\"", node.uuid()) } else { format!("\"{}\"", node.uuid()) }
+}
+
+/// Returns the DOT identifier holding the outputs of the Node.
+fn exit(node: &Node, layers: bool) -> String {
+    if node.is_leaf_node() && layers { format!("\"{}:{}\"", node.uuid(), node.mesh().layers().len() - 1) } else { format!("\"{}\"", node.uuid()) }
+}
+
+/// Returns the label of a NodeRange: All, or its keys and key maps.
+fn range(node_range: &NodeRange) -> String {
+    match node_range {
+        NodeRange::All => { String::from("All") }
+        NodeRange::Selective(key_chain) => { escape(&format!("[{}]", key_chain.join(", "))) }
+        NodeRange::Mapped(key_maps) => { escape(&format!("[{}]", key_maps.iter().map(|key_map| key_map.to_string()).collect::<Vec<String>>().join(", "))) }
+    }
+}
+
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -5,6 +5,12 @@
  * (c) Copyright by Christian Potts
  */
 
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use arrayfire::{Array, cols};
+use rustc_hash::FxHashMap;
+use crate::node::tensor::Tensor;
+
 #[derive(serde::Serialize, serde::Deserialize)]
 pub(crate) struct Edges {
     pub(crate) input_from_parent: NodeRange,
@@ -37,6 +43,13 @@ impl Edges {
         }
         Ok(())
     }
+
+    /// Renames the keys whose name is in `names` in every Selective NodeRange.
+    pub(crate) fn rename(&mut self, names: &FxHashMap<String, String>) {
+        self.input_from_parent.rename(names);
+        self.output_to_parent.rename(names);
+        self.input_from_peer_output.iter_mut().for_each(|(_, node_range)| node_range.rename(names));
+    }
 }
 
 /// LinkType flag used in Edges link function.
@@ -48,8 +61,185 @@ pub enum LinkType {
 }
 
 /// Establishes if all values from a Node are to be linked if only certain values will be passed.
-#[derive(serde::Serialize, serde::Deserialize, Clone)]
+///
+/// `Mapped` passes the entries selected by each KeyMap, which may match keys by pattern, keep a range of columns and rename the entry.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
 pub enum NodeRange {
     Selective(Vec<String>),
     All,
-}
\ No newline at end of file
+    Mapped(Vec<KeyMap>),
+}
+
+impl NodeRange {
+    /// Returns the entries of `tensor` passed on by the NodeRange, under the keys they are passed on with.
+    ///
+    /// Entries are selected in the order of the Tensor, KeyMap by KeyMap for a Mapped NodeRange.
+    pub(crate) fn select(&self, tensor: &Tensor) -> Vec<(String, Array<f64>)> {
+        match self {
+            NodeRange::All => { tensor.hash_map.iter().map(|(k, v)| (k.clone(), v.clone())).collect() }
+            NodeRange::Selective(key_chain) => { tensor.hash_map.iter().filter(|(k, _)| key_chain.contains(k)).map(|(k, v)| (k.clone(), v.clone())).collect() }
+            NodeRange::Mapped(key_maps) => { key_maps.iter().flat_map(|key_map| key_map.select(tensor)).collect() }
+        }
+    }
+
+    /// Renames the keys of a Selective or Mapped NodeRange whose name is in `names`.
+    pub(crate) fn rename(&mut self, names: &FxHashMap<String, String>) {
+        let rename = |key: &mut String| if let Some(name) = names.get(key) { *key = name.clone() };
+        match self {
+            NodeRange::Selective(key_chain) => { key_chain.iter_mut().for_each(rename); }
+            NodeRange::Mapped(key_maps) => { key_maps.iter_mut().for_each(|key_map| { rename(&mut key_map.pattern); key_map.alias.iter_mut().for_each(rename); }); }
+            NodeRange::All => {}
+        }
+    }
+}
+
+/// Selects the entries whose key matches a pattern, as part of a Mapped NodeRange.
+///
+/// In a pattern, `*` matches any sequence of characters and `?` any single character, so `features` matches a single key and `features_*` every key with that prefix.
+/// System entries are never selected. Entries may be reduced to a range of their columns, and passed on under an alias.
+///
+/// # Example(s)
+/// ```
+/// use anneml::node::edges::{KeyMap, NodeRange};
+///
+/// // Pass columns 3 to 6 of the features as the score, along with every embedding.
+/// let node_range = NodeRange::Mapped(vec![KeyMap::new("features").with_columns(3..7).with_alias("score"), KeyMap::new("embedding_*")]);
+/// ```
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct KeyMap {
+    pattern: String,
+    columns: Option<(usize, usize)>,
+    alias: Option<String>,
+}
+
+impl KeyMap {
+    /// Selects the entries whose key matches `pattern`.
+    pub fn new(pattern: &str) -> Self {
+        KeyMap { pattern: pattern.to_string(), columns: None, alias: None }
+    }
+
+    /// Keeps the `columns` of the selected entries, the range being clipped to the size of each entry.
+    pub fn with_columns(mut self, columns: Range<usize>) -> Self {
+        self.columns = Some((columns.start, columns.end));
+        self
+    }
+
+    /// Passes the selected entries on under `alias`. As entries sharing a key replace each other, the pattern should match a single key.
+    pub fn with_alias(mut self, alias: &str) -> Self {
+        self.alias = Some(alias.to_string());
+        self
+    }
+
+    /// Acquire the pattern matched against keys.
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Acquire the range of columns kept, if any.
+    pub fn columns(&self) -> Option<Range<usize>> {
+        self.columns.map(|(start, end)| start..end)
+    }
+
+    /// Acquire the key the entries are passed on under, if renamed.
+    pub fn alias(&self) -> Option<&str> {
+        self.alias.as_deref()
+    }
+
+    /// Returns whether `key` is selected.
+    pub(crate) fn matches(&self, key: &str) -> bool {
+        !key.contains("_SYSTEM") && glob(self.pattern.as_bytes(), key.as_bytes())
+    }
+
+    /// Returns the selected entries of `tensor`, sliced and renamed. Entries with no column in range are left out.
+    fn select(&self, tensor: &Tensor) -> Vec<(String, Array<f64>)> {
+        tensor.hash_map.iter().filter(|(key, _)| self.matches(key)).filter_map(|(key, value)| {
+            let value = match self.columns {
+                None => { value.clone() }
+                Some((start, end)) => {
+                    let end = end.min(value.dims()[1] as usize);
+                    if start >= end { return None }
+                    cols(value, start as i64, end as i64 - 1)
+                }
+            };
+            Some((self.alias.clone().unwrap_or_else(|| key.clone()), value))
+        }).collect()
+    }
+}
+
+impl Display for KeyMap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.pattern)?;
+        if let Some((start, end)) = self.columns { write!(f, "[{}..{}]", start, end)?; }
+        if let Some(alias) = &self.alias { write!(f, " -> {}", alias)?; }
+        Ok(())
+    }
+}
+
+/// Matches `key` against a pattern in which `*` matches any sequence of characters and `?` any single character.
+fn glob(pattern: &[u8], key: &[u8]) -> bool {
+    match (pattern.first(), key.first()) {
+        (None, None) => { true }
+        (Some(b'*'), _) => { glob(&pattern[1..], key) || (!key.is_empty() && glob(pattern, &key[1..])) }
+        (Some(b'?'), Some(_)) => { glob(&pattern[1..], &key[1..]) }
+        (Some(p), Some(k)) => { p == k && glob(&pattern[1..], &key[1..]) }
+        _ => { false }
+    }
+}
+
+/// Reasons a link between Nodes is rejected.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LinkError {
+    /// Peer links are declared on the vertex holding both peers.
+    NotVertex,
+    /// No child sits at the (column, row) position.
+    OutOfBounds((usize, usize)),
+    /// A Node can not read its own outputs.
+    SelfLink,
+    /// The Node already reads the outputs of the peer at the (column, row) position.
+    DuplicateLink((usize, usize)),
+    /// System keys can not be linked.
+    ReservedKey(String),
+    /// The key is listed more than once.
+    DuplicateKey(String),
+    /// The key is not an output of the linked leaf.
+    UnknownKey(String),
+    /// The Edges rejected the link, for the given reason.
+    Rejected(&'static str),
+    /// The range of columns selected for the key is empty.
+    EmptyColumns(String),
+}
+
+impl Display for LinkError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LinkError::NotVertex => { write!(f, "Peer links require a vertex.") }
+            LinkError::OutOfBounds((x, y)) => { write!(f, "No child at ({}, {}).", x, y) }
+            LinkError::SelfLink => { write!(f, "Node can not read its own outputs.") }
+            LinkError::DuplicateLink((x, y)) => { write!(f, "Node already reads the outputs of ({}, {}).", x, y) }
+            LinkError::ReservedKey(key) => { write!(f, "Key {} is reserved.", key) }
+            LinkError::DuplicateKey(key) => { write!(f, "Key {} is listed more than once.", key) }
+            LinkError::UnknownKey(key) => { write!(f, "Key {} is not an output of the linked leaf.", key) }
+            LinkError::EmptyColumns(key) => { write!(f, "Key {} selects no column.", key) }
+            LinkError::Rejected(reason) => { write!(f, "{}", reason) }
+        }
+    }
+}
+
+/// Describes a LinkError without the position or key it names, for functions whose errors are `&'static str`.
+impl From<LinkError> for &'static str {
+    fn from(error: LinkError) -> Self {
+        match error {
+            LinkError::NotVertex => { "Peer links require a vertex." }
+            LinkError::OutOfBounds(_) => { "No child at the linked position." }
+            LinkError::SelfLink => { "Node can not read its own outputs." }
+            LinkError::DuplicateLink(_) => { "Node already reads the outputs of the linked peer." }
+            LinkError::ReservedKey(_) => { "Linked key is reserved." }
+            LinkError::DuplicateKey(_) => { "Linked key is listed more than once." }
+            LinkError::UnknownKey(_) => { "Linked key is not an output of the linked leaf." }
+            LinkError::EmptyColumns(_) => { "Linked key selects no column." }
+            LinkError::Rejected(reason) => { reason }
+        }
+    }
+}
+
+impl std::error::Error for LinkError {}
@@ -0,0 +1,64 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use std::sync::Arc;
+use crate::node::registry::Registry;
+use crate::node::tensor::Tensor;
+
+/// Constructs a Function from the configuration it recorded when it was saved.
+pub type FunctionConstructor = fn(&str) -> Result<Box<dyn Function>, &'static str>;
+
+static REGISTRY: Registry<dyn Function> = Registry::new("Function is not registered.");
+
+/// A deterministic transformation of named arrays, wrapped by a function Node.
+///
+/// Function Nodes hold no learned values. They only record the Function's name and configuration,
+/// the Function being rebuilt by the constructor registered under its name.
+pub trait Function: Send + Sync {
+    /// Name the Function's constructor is registered under.
+    fn name(&self) -> &str;
+
+    /// Configuration handed back to the constructor when the Function is rebuilt.
+    fn configuration(&self) -> String { String::new() }
+
+    /// Transforms the inputs of the Node into its named outputs.
+    ///
+    /// # Errors
+    /// Returns an Err if the inputs can not be transformed.
+    fn call(&self, inputs: &Tensor) -> Result<Tensor, &'static str>;
+}
+
+/// Registers a Function constructor by name, replacing any constructor previously registered under that name.
+///
+/// # Example(s)
+/// ```
+/// use anneml::node::function::{Function, register_function};
+/// use anneml::node::tensor::Tensor;
+///
+/// struct Clamp;
+///
+/// impl Function for Clamp {
+///     fn name(&self) -> &str { "clamp" }
+///     fn call(&self, inputs: &Tensor) -> Result<Tensor, &'static str> {
+///         let input = inputs.get("input").ok_or("Missing input.")?;
+///         Ok(Tensor::new(&[("clamped", arrayfire::clamp(input, &0.0, &1.0, false))]))
+///     }
+/// }
+///
+/// register_function("clamp", |_| Ok(Box::new(Clamp)));
+/// ```
+pub fn register_function(name: &str, constructor: FunctionConstructor) {
+    REGISTRY.register(name, constructor);
+}
+
+/// Rebuilds a registered Function from its name and configuration.
+///
+/// # Errors
+/// Returns an Err if no constructor is registered under `name`, or if the constructor rejects the configuration.
+pub(crate) fn resolve_function(name: &str, configuration: &str) -> Result<Arc<dyn Function>, &'static str> {
+    REGISTRY.resolve(name, configuration)
+}
@@ -0,0 +1,98 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Default, Debug)]
+pub struct Kernel {
+    size: (usize, usize),
+    stride: (usize, usize),
+    padding: (usize, usize),
+    channels: (usize, usize),
+    rows: usize,
+}
+
+impl Kernel {
+    /// Create a Kernel that configures a two-dimensional convolution.
+    ///
+    /// Sizes, strides and paddings are given as (rows, columns) and channels as (input, output).
+    /// The previous layer's values are read as input channels of `rows` by `columns` planes, each plane being stored in column-major order.
+    /// The number of columns follows from the size of the previous layer.
+    ///
+    /// # Example(s)
+    /// ```
+    /// use anneml::node::attribute::{Activation, Attribute, CellType, TensorDescriptor};
+    /// use anneml::node::kernel::Kernel;
+    /// use anneml::node::scope::Scope;
+    ///
+    /// // 3x3 kernel over a single 28 row image, producing 4 feature maps.
+    /// let kernel = Kernel::new((3,3), (1,1), (1,1), (1,4), 28);
+    /// let descriptor = vec![("_SYSTEM_KERNELS", TensorDescriptor::RandN), ("_SYSTEM_KERNEL_BIASES", TensorDescriptor::Const(0.0))];
+    /// let attribute = Attribute::new(Activation::TanH, CellType::Conv2d(kernel), descriptor, Scope::new(0,1));
+    /// ```
+    pub fn new(size: (usize, usize), stride: (usize, usize), padding: (usize, usize), channels: (usize, usize), rows: usize) -> Self {
+        Kernel { size, stride, padding, channels, rows }
+    }
+
+    /// Create a Kernel that configures a one-dimensional convolution.
+    ///
+    /// The previous layer's values are read as input channels of equal length, one channel after the other.
+    pub fn new_1d(size: usize, stride: usize, padding: usize, channels: (usize, usize)) -> Self {
+        Kernel { size: (1, size), stride: (1, stride), padding: (0, padding), channels, rows: 1 }
+    }
+
+    pub(crate) fn size(&self) -> (usize, usize) {
+        self.size
+    }
+
+    pub(crate) fn stride(&self) -> (usize, usize) {
+        self.stride
+    }
+
+    pub(crate) fn padding(&self) -> (usize, usize) {
+        self.padding
+    }
+
+    pub(crate) fn channels(&self) -> (usize, usize) {
+        self.channels
+    }
+
+    /// Returns the (rows, columns) of each input plane given the size of the previous layer.
+    ///
+    /// # Errors
+    /// Returns an Err if the previous layer can not be split into input channels of `rows` rows.
+    pub(crate) fn input_shape(&self, width: usize) -> Result<(usize, usize), &'static str> {
+        let plane = self.channels.0 * self.rows;
+        if plane == 0 || !width.is_multiple_of(plane) { return Err("Layer size is not divisible into kernel input channels.") }
+        Ok((self.rows, width / plane))
+    }
+
+    /// Returns the (rows, columns) of each output plane given the size of the previous layer.
+    ///
+    /// # Errors
+    /// Returns an Err if the kernel is invalid or does not fit within the padded input.
+    pub(crate) fn output_shape(&self, width: usize) -> Result<(usize, usize), &'static str> {
+        let (rows, cols) = self.input_shape(width)?;
+        if self.stride.0 == 0 || self.stride.1 == 0 { return Err("Kernel stride must be greater than zero.") }
+        if self.padding.0 >= self.size.0 || self.padding.1 >= self.size.1 { return Err("Kernel padding must be smaller than the kernel size.") }
+        if self.size.0 > rows + 2 * self.padding.0 || self.size.1 > cols + 2 * self.padding.1 { return Err("Kernel is larger than the padded input.") }
+        Ok(((rows + 2 * self.padding.0 - self.size.0) / self.stride.0 + 1, (cols + 2 * self.padding.1 - self.size.1) / self.stride.1 + 1))
+    }
+
+    /// Returns the size of the layer produced by the kernel given the size of the previous layer.
+    pub(crate) fn output_width(&self, width: usize) -> Result<usize, &'static str> {
+        let (rows, cols) = self.output_shape(width)?;
+        Ok(rows * cols * self.channels.1)
+    }
+
+    /// Returns the size of the layer produced by pooling with the kernel given the size of the previous layer.
+    ///
+    /// # Errors
+    /// Returns an Err if the input and output channels differ, as pooling keeps every channel.
+    pub(crate) fn pool_width(&self, width: usize) -> Result<usize, &'static str> {
+        if self.channels.0 != self.channels.1 { return Err("Pooling kernel must have equal input and output channels.") }
+        self.output_width(width)
+    }
+}
@@ -5,16 +5,24 @@
  * (c) Copyright by Christian Potts
  */
 
-use crate::node::attribute::{Attribute, TensorDescriptor};
+use std::sync::Arc;
+use arrayfire::{abs, any_true_all, constant, dim4, eq, lt, RandomEngine};
+use crate::node::attribute::{Attribute, CellType, Normalization, TensorDescriptor};
+use crate::node::cell::{Cell, resolve_cell};
 use crate::node::domain::Domain;
 use crate::node::tensor::Tensor;
-use crate::node::utils::new_array;
+use crate::node::utils::{build_array, new_array, random_engine, stream_seed};
 
 #[derive(serde::Serialize, serde::Deserialize)]
 pub(crate) struct Layer {
     pub(crate) tensor: Tensor,
     pub(crate) attribute: Attribute,
-    pub(crate) domain: Domain
+    pub(crate) domain: Domain,
+    stream: u64,
+    passes: u64,
+    state: Vec<String>,
+    #[serde(skip)]
+    cell: Option<Arc<dyn Cell>>,
 }
 
 impl Layer {
@@ -22,19 +30,160 @@ impl Layer {
     /// # Return Values
     /// Layer
     pub(crate) fn new(attribute: Attribute, tensor: Tensor) -> Self {
-        Layer { tensor, attribute, domain: Domain::default() }
+        Layer { tensor, attribute, domain: Domain::default(), stream: 0, passes: 0, state: vec![], cell: None }
     }
 
     /// Builds a layer that represents the connections to other layers in the slice.
     ///
     /// Indices represent the Neighboring Layers and their column size. contained inside.
-    pub(crate) fn build(&mut self, index: usize, slice: &[usize]) {
+    /// Layers that are not `dense` receive no weighted values, so no connection to them is built.
+    /// Random values are drawn from the layer's own random stream, seeded by `stream`.
+    ///
+    /// # Errors
+    /// Returns an Err if the dropout rate is not within [0, 1), or if an explicit TensorDescriptor does not match the dimensions computed from the topology.
+    pub(crate) fn build(&mut self, index: usize, slice: &[usize], dense: &[bool], stream: u64) -> Result<(), &'static str> {
+        if !(0.0..1.0).contains(&self.attribute.dropout()) { return Err("Dropout rate must be within [0, 1).") }
+        (self.stream, self.passes) = (stream, 0);
+        let engine = &random_engine(stream);
         self.domain = Domain::new(self.attribute.scope(), index, slice.len() as isize);
+        self.build_cell(index, slice, engine)?;
         let (start, end) = (self.domain.start(), self.domain.end());
-        let mut slice = slice[start..=end].to_vec();
-        if slice.len() > 1 {
-            self.tensor.insert("_SYSTEM_WEIGHTS", new_array(TypeTensor::Weight, &mut slice, &TensorDescriptor::Const(0.0), self.attribute.description("_SYSTEM_WEIGHTS"), start, index));
-            self.tensor.insert("_SYSTEM_BIASES", new_array(TypeTensor::Bias, &mut slice, &TensorDescriptor::Const(0.0), self.attribute.description("_SYSTEM_BIASES"), start, index));
+        let mut slice = slice[start..=end].iter().enumerate()
+            .map(|(i, width)| if start + i == index || dense[start + i] { *width } else { 0 }).collect::<Vec<usize>>();
+        if slice.iter().enumerate().any(|(i, width)| start + i != index && *width > 0) {
+            self.tensor.insert("_SYSTEM_WEIGHTS", new_array(TypeTensor::Weight, &mut slice, &TensorDescriptor::Const(0.0), self.attribute.description("_SYSTEM_WEIGHTS"), start, index, engine)?);
+            self.tensor.insert("_SYSTEM_BIASES", new_array(TypeTensor::Bias, &mut slice, &TensorDescriptor::Const(0.0), self.attribute.description("_SYSTEM_BIASES"), start, index, engine)?);
+        }
+        Ok(())
+    }
+
+    /// Builds the parameters and state required by the layer's CellType and Normalization.
+    fn build_cell(&mut self, index: usize, slice: &[usize], engine: &RandomEngine) -> Result<(), &'static str> {
+        let width = slice[index] as u64;
+        let gates = self.attribute.cell_type().gates();
+        if gates > 0 {
+            self.tensor.insert("_SYSTEM_GATE_WEIGHTS", build_array(self.attribute.description("_SYSTEM_GATE_WEIGHTS"), dim4!(width, 2 * width, gates, 1), engine)?);
+            self.tensor.insert("_SYSTEM_GATE_BIASES", build_array(self.attribute.description("_SYSTEM_GATE_BIASES"), dim4!(width, 1, gates, 1), engine)?);
+            self.tensor.insert("_SYSTEM_HIDDEN", constant(0.0, dim4!(1, width, 1, 1)));
+        }
+        match self.attribute.cell_type() {
+            CellType::Lstm => { self.tensor.insert("_SYSTEM_CELL", constant(0.0, dim4!(1, width, 1, 1))); }
+            CellType::Conv1d(kernel) | CellType::Conv2d(kernel) => {
+                let (size, channels) = (kernel.size(), kernel.channels());
+                let kernels = build_array(self.attribute.description("_SYSTEM_KERNELS"), dim4!(size.0 as u64, size.1 as u64, channels.0 as u64, channels.1 as u64), engine)?;
+                let biases = build_array(self.attribute.description("_SYSTEM_KERNEL_BIASES"), dim4!(1, channels.1 as u64, 1, 1), engine)?;
+                self.tensor.insert("_SYSTEM_KERNELS", kernels);
+                self.tensor.insert("_SYSTEM_KERNEL_BIASES", biases);
+            }
+            CellType::Attention(dimension, _) => {
+                let dimension = *dimension as u64;
+                let weights = build_array(self.attribute.description("_SYSTEM_ATTENTION_WEIGHTS"), dim4!(dimension, dimension, 4, 1), engine)?;
+                let biases = build_array(self.attribute.description("_SYSTEM_ATTENTION_BIASES"), dim4!(1, dimension, 4, 1), engine)?;
+                self.tensor.insert("_SYSTEM_ATTENTION_WEIGHTS", weights);
+                self.tensor.insert("_SYSTEM_ATTENTION_BIASES", biases);
+            }
+            CellType::Embedding(_, vocabulary, dimension) => {
+                let embeddings = build_array(self.attribute.description("_SYSTEM_EMBEDDINGS"), dim4!(*vocabulary as u64, *dimension as u64, 1, 1), engine)?;
+                self.tensor.insert("_SYSTEM_EMBEDDINGS", embeddings);
+            }
+            CellType::Rbf(centers) => {
+                let previous = slice[index.saturating_sub(1)] as u64;
+                let centers_array = build_array(self.attribute.description("_SYSTEM_RBF_CENTERS"), dim4!(*centers as u64, previous, 1, 1), engine)?;
+                let description = self.attribute.description("_SYSTEM_RBF_WIDTHS");
+                let widths = build_array(description, dim4!(*centers as u64, 1, 1, 1), engine)?;
+                // Explicit widths are used as given, while drawn and constant widths keep their magnitude.
+                let widths = match description {
+                    TensorDescriptor::Values(..) | TensorDescriptor::File(_) => {
+                        if any_true_all(&lt(&widths, &0.0, false)).0 { return Err("Radial basis function widths must not be negative.") }
+                        widths
+                    }
+                    _ => { abs(&widths) }
+                };
+                if any_true_all(&eq(&widths, &0.0, false)).0 { return Err("Radial basis function widths must not be zero.") }
+                self.tensor.insert("_SYSTEM_RBF_CENTERS", centers_array);
+                self.tensor.insert("_SYSTEM_RBF_WIDTHS", widths);
+            }
+            CellType::Som(rows, columns, _, _) => {
+                let previous = slice[index.saturating_sub(1)] as u64;
+                let weights = build_array(self.attribute.description("_SYSTEM_SOM_WEIGHTS"), dim4!((rows * columns) as u64, previous, 1, 1), engine)?;
+                self.tensor.insert("_SYSTEM_SOM_WEIGHTS", weights);
+            }
+            CellType::Custom(..) => {
+                let (cell, previous) = (self.cell()?, slice[index.saturating_sub(1)]);
+                for (key, dims) in cell.parameters(width as usize, previous) {
+                    let parameter = build_array(self.attribute.description(&key), dims, engine)?;
+                    self.tensor.insert(&key, parameter);
+                }
+                self.state = cell.state(width as usize, previous).into_iter().map(|(key, dims)| { self.tensor.insert(&key, constant(0.0, dims)); key }).collect();
+            }
+            _ => {}
+        }
+        match self.attribute.normalization() {
+            Normalization::None => {}
+            normalization => {
+                self.tensor.insert("_SYSTEM_NORM_SCALE", constant(1.0, dim4!(1, width, 1, 1)));
+                self.tensor.insert("_SYSTEM_NORM_SHIFT", constant(0.0, dim4!(1, width, 1, 1)));
+                if let Normalization::Batch(_) = normalization {
+                    self.tensor.insert("_SYSTEM_RUNNING_MEAN", constant(0.0, dim4!(1, width, 1, 1)));
+                    self.tensor.insert("_SYSTEM_RUNNING_VARIANCE", constant(1.0, dim4!(1, width, 1, 1)));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates the random stream of the next training pass.
+    ///
+    /// Each pass draws from a new stream derived from the layer's stream, keeping successive passes reproducible.
+    pub(crate) fn next_pass(&mut self) -> RandomEngine {
+        self.passes += 1;
+        random_engine(stream_seed(self.stream, 0, self.passes))
+    }
+
+    /// Clears the state carried across passes by recurrent and custom cells.
+    pub(crate) fn reset_state(&mut self) {
+        ["_SYSTEM_HIDDEN", "_SYSTEM_CELL"].iter().map(|key| key.to_string()).chain(self.state.clone()).for_each(|key| {
+            if let Some(state) = self.tensor.hash_map.get_mut(&key) { *state = constant(0.0, state.dims()); }
+        });
+    }
+
+    /// Counts the values of the layer's learnable parameters, excluding the state carried across passes.
+    pub(crate) fn parameters(&self) -> usize {
+        let state = ["_SYSTEM_HIDDEN", "_SYSTEM_CELL", "_SYSTEM_RUNNING_MEAN", "_SYSTEM_RUNNING_VARIANCE"];
+        self.tensor.hash_map.iter().filter(|(key, _)| key.contains("_SYSTEM") && !state.contains(&key.as_str()) && !self.state.contains(key))
+            .map(|(_, value)| value.elements()).sum()
+    }
+
+    /// Returns the custom Cell of the layer, rebuilding it from the registry on first use.
+    ///
+    /// # Errors
+    /// Returns an Err if the layer's CellType is not custom, or if its Cell can not be rebuilt.
+    pub(crate) fn cell(&mut self) -> Result<Arc<dyn Cell>, &'static str> {
+        if self.cell.is_none() {
+            let CellType::Custom(name, configuration) = self.attribute.cell_type() else { return Err("Layer does not have a custom cell.") };
+            self.cell = Some(resolve_cell(name, configuration)?);
+        }
+        Ok(self.cell.clone().unwrap())
+    }
+
+    /// Returns whether the layer's cell accumulates weighted values from the layers within Scope.
+    ///
+    /// Custom cells are asked through the cached Cell, and are not dense if they can not be rebuilt.
+    pub(crate) fn is_dense(&mut self) -> bool {
+        match self.attribute.cell_type() {
+            CellType::Custom(..) => { self.cell().map(|cell| cell.is_dense()).unwrap_or(false) }
+            cell_type => { cell_type.is_dense() }
+        }
+    }
+
+    /// Returns the layer size produced by the layer's cell given the size of its source, if the cell determines its own size.
+    ///
+    /// # Errors
+    /// Returns an Err if the cell can not be applied to its source, or if a custom Cell can not be rebuilt.
+    pub(crate) fn output_width(&mut self, width: usize) -> Result<Option<usize>, &'static str> {
+        match self.attribute.cell_type() {
+            CellType::Custom(..) => { self.cell()?.output_width(width) }
+            cell_type => { cell_type.output_width(width) }
         }
     }
 
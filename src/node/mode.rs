@@ -0,0 +1,16 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+/// Determines whether a pass through a Node is used for training or for inference.
+///
+/// Layers that behave differently while training (e.g. batch normalization) consult the Mode of each pass.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+pub enum Mode {
+    Train,
+    #[default]
+    Eval,
+}
@@ -7,11 +7,20 @@
 
 use std::fs::File;
 use std::io::{BufReader, Read, Write};
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
+use uuid::Uuid;
+use crate::node::config::{NetworkConfig, NodeConfig};
+use crate::node::dot::graph;
+use crate::node::edges::NodeRange;
+use crate::node::mode::Mode;
 use crate::node::node::Node;
+use crate::node::processor::processor::Processor;
+use crate::node::summary::Summary;
+use crate::node::tensor::Tensor;
 
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct Network {
+    seed: u64,
     node: Arc<RwLock<Node>>,
 }
 
@@ -37,7 +46,35 @@ impl Network {
     /// let network = Network::new(node);
     /// ```
     pub fn new(node: Node) -> Self {
-        Network { node: Arc::new(RwLock::new(node)) }
+        Network::with_seed(node, 0)
+    }
+
+    /// Create a Network whose random initialization is driven by `seed`.
+    ///
+    /// Every layer draws from its own random stream, derived from the seed, the owning Node's UUID and the layer index.
+    /// Building the same Network twice therefore yields identical values, independent of traversal order or other Networks.
+    /// The seed is stored alongside the Network when saved.
+    ///
+    /// # Example(s)
+    /// ```
+    /// use uuid::Uuid;
+    /// use anneml::node::attribute::Attribute;
+    /// use anneml::node::network::Network;
+    /// use anneml::node::node::{Node, NodeType};
+    ///
+    /// let attribute = Attribute::default();
+    /// let leaf_node = NodeType::Leaf(attribute.clone(), vec![(attribute.clone(), 3), (attribute.clone(), 2)]);
+    /// let network = Network::with_seed(Node::new(Uuid::new_v4(), leaf_node), 42);
+    /// assert_eq!(42, network.seed());
+    /// ```
+    pub fn with_seed(mut node: Node, seed: u64) -> Self {
+        node.set_seed(seed);
+        Network { seed, node: Arc::new(RwLock::new(node)) }
+    }
+
+    /// Acquire the seed used for random initialization.
+    pub fn seed(&self) -> u64 {
+        self.seed
     }
 
     /// Acquire a Networks wrapped node reference.
@@ -45,9 +82,225 @@ impl Network {
         self.node.clone()
     }
 
+    /// Searches the Network for the Node with `uuid`.
+    ///
+    /// The root Node is acquired through `Network::node`, so only its descendants are searched.
+    pub fn find_by_uuid(&self, uuid: Uuid) -> Option<Arc<Mutex<Node>>> {
+        self.node.read().unwrap().find_by_uuid(uuid)
+    }
+
+    /// Follows a `/` separated path of Node names (or UUIDs), starting with the root Node.
+    ///
+    /// The root Node is acquired through `Network::node`, so a path naming only the root returns None.
+    ///
+    /// # Example(s)
+    /// ```
+    /// use uuid::Uuid;
+    /// use anneml::node::attribute::Attribute;
+    /// use anneml::node::network::Network;
+    /// use anneml::node::node::{Node, NodeType, VertexKind};
+    ///
+    /// let attribute = Attribute::default();
+    /// let classifier = Node::new(Uuid::new_v4(), NodeType::Leaf(attribute.clone(), vec![(attribute.clone(), 2)])).with_name("classifier");
+    /// let encoder = Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, attribute.clone(), classifier, attribute.clone())).with_name("encoder");
+    /// let root = Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, attribute.clone(), encoder, attribute.clone())).with_name("root");
+    /// let network = Network::new(root);
+    ///
+    /// let classifier = network.find_by_path("root/encoder/classifier").unwrap();
+    /// assert_eq!(Some("classifier"), classifier.lock().unwrap().name());
+    /// assert!(network.find_by_path("other/encoder").is_none());
+    /// ```
+    pub fn find_by_path(&self, path: &str) -> Option<Arc<Mutex<Node>>> {
+        let (root, rest) = path.split_once('/')?;
+        let node = self.node.read().unwrap();
+        if !node.matches(root) { return None }
+        node.find_by_path(rest)
+    }
+
+    /// Replaces the Node with `uuid` by `replacement`, returning the replaced Node so it can be swapped back.
+    ///
+    /// The swap waits for passes in progress to finish, so it always happens between two passes.
+    /// The replacement takes over the Edges of the replaced Node, staying connected to its parent and peers without rebuilding them,
+    /// and draws from the Network's seed. As leaf outputs are keyed by the leaf's UUID, a newer version of a leaf keeps the UUID of the leaf it replaces.
+    /// If the replaced Node has been built, a replacement that has not is built from the inputs the replaced Node last received,
+    /// so the following passes do not need to build the Network again. Its recurrent state is then reset.
+    ///
+    /// # Errors
+    /// Returns an Err if no descendant of the root Node has `uuid`, or if the replacement is not compatible with the replaced Node:
+    /// it has to provide the same output entries, and may only expect input entries the replaced Node receives.
+    /// Returns the Err raised while building the replacement if it can not be built from the inputs of the replaced Node.
+    /// The replaced Node stays in place whenever an Err is returned.
+    ///
+    /// # Example(s)
+    /// ```
+    /// use uuid::Uuid;
+    /// use anneml::node::attribute::Attribute;
+    /// use anneml::node::network::Network;
+    /// use anneml::node::node::{Node, NodeType, VertexKind};
+    ///
+    /// let attribute = Attribute::default();
+    /// let uuid = Uuid::new_v4();
+    /// let leaf = |count| Node::new(uuid, NodeType::Leaf(attribute.clone(), vec![(attribute.clone(), count)]));
+    /// let network = Network::new(Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, attribute.clone(), leaf(2), attribute.clone())));
+    ///
+    /// let previous = network.hot_swap(uuid, leaf(2)).unwrap();
+    /// assert!(network.hot_swap(uuid, leaf(3)).is_err());
+    /// network.hot_swap(uuid, previous).unwrap();
+    /// ```
+    pub fn hot_swap(&self, uuid: Uuid, mut replacement: Node) -> Result<Node, &'static str> {
+        let root = self.node.write().unwrap();
+        let child = root.find_by_uuid(uuid).ok_or("Node is not in the network.")?;
+        let mut child = child.lock().unwrap();
+        child.check_replacement(&replacement)?;
+        replacement.set_seed(self.seed);
+        if child.is_built() && !replacement.is_built() {
+            let mut inputs = Tensor::default();
+            child.mesh().layers()[0].lock().unwrap().tensor.iter().filter(|(key, _)| !key.contains("_SYSTEM")).for_each(|(key, value)| inputs.insert(key, value.clone()));
+            let input_from_parent = std::mem::replace(&mut replacement.mesh().edges().input_from_parent, NodeRange::All);
+            replacement.traverse(&inputs, true, Mode::Eval)?;
+            replacement.mesh().edges().input_from_parent = input_from_parent;
+            replacement.reset_state();
+        }
+        std::mem::swap(&mut *child.mesh().edges(), &mut *replacement.mesh().edges());
+        Ok(std::mem::replace(&mut *child, replacement))
+    }
+
+    /// Renders the Network as a Graphviz DOT digraph.
+    ///
+    /// Vertices are linked to their children by the input (solid) and output (dashed) links of the children's Edges, and children to their peers by dotted links.
+    /// Links are labeled with their NodeRange, either All or the list of keys or key maps that flow through them.
+    /// If `layers` is true, each leaf is drawn as a cluster of its layers with their sizes, cell types and activations, linked by the connections their Scopes span.
+    ///
+    /// # Example(s)
+    /// ```
+    /// use uuid::Uuid;
+    /// use anneml::node::attribute::Attribute;
+    /// use anneml::node::network::Network;
+    /// use anneml::node::node::{Node, NodeType, VertexKind};
+    ///
+    /// let attribute = Attribute::default();
+    /// let leaf_node = Node::new(Uuid::new_v4(), NodeType::Leaf(attribute.clone(), vec![(attribute.clone(), 2)])).with_name("classifier");
+    /// let network = Network::new(Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, attribute.clone(), leaf_node, attribute.clone())));
+    ///
+    /// let dot = network.to_dot(true);
+    /// assert!(dot.starts_with("digraph"));
+    /// assert!(dot.contains("classifier"));
+    /// ```
+    pub fn to_dot(&self, layers: bool) -> String {
+        graph(&self.node.read().unwrap(), layers)
+    }
+
+    /// Reports the structure of the Network.
+    ///
+    /// The Summary lists every Node with its role, name, UUID, input and output keys and layers,
+    /// each layer with its size, cell type, activation, scope and parameter count, along with the total parameter count and memory footprint.
+    /// Keys, sizes, parameters and memory reflect the Network's last pass, so they are only complete once it is built.
+    ///
+    /// # Example(s)
+    /// ```
+    /// use uuid::Uuid;
+    /// use anneml::node::attribute::Attribute;
+    /// use anneml::node::network::Network;
+    /// use anneml::node::node::{Node, NodeType, VertexKind};
+    ///
+    /// let attribute = Attribute::default();
+    /// let leaf_node = Node::new(Uuid::new_v4(), NodeType::Leaf(attribute.clone(), vec![(attribute.clone(), 3), (attribute.clone(), 2)]));
+    /// let network = Network::new(Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, attribute.clone(), leaf_node, attribute.clone())));
+    ///
+    /// let summary = network.summary();
+    /// assert_eq!(2, summary.nodes().len());
+    /// assert_eq!(vec![0, 3, 2], summary.nodes()[1].layers().iter().map(|layer| layer.width()).collect::<Vec<usize>>());
+    /// println!("{}", summary);
+    /// ```
+    pub fn summary(&self) -> Summary {
+        Summary::new(&self.node.read().unwrap())
+    }
+
+    /// Creates a Network from a TOML definition file. See `Network::from_config_str`.
+    ///
+    /// # Errors
+    /// Returns an Err if the file can not be read, or if its definition is invalid.
+    pub fn from_config(path: &str) -> Result<Network, &'static str> {
+        Network::from_config_str(&std::fs::read_to_string(path).map_err(|_| "Unable to read definition file.")?)
+    }
+
+    /// Creates a Network from a TOML definition of its architecture.
+    ///
+    /// The definition holds an optional `seed` and a `root` Node table. Each Node table may hold:
+    /// 1) `name` and `uuid`, generated if missing.
+    /// 2) `inputs_from_parent`, `outputs_to_parent` and `inputs_from_peers` (`peer` name and `range`), which set its Edges.
+    /// 3) For a leaf, its `input` Attribute and its hidden `layers`, each with a `count` and an `attribute`.
+    /// 4) For a vertex, its `kind` (a VertexKind, `Aggregate` if left out), its `input` and `output` Attributes and its `columns` of `children`.
+    /// 5) For a function Node, its `function` table, with the `name` and `configuration` of a registered Function.
+    ///
+    /// Attribute fields that are left out take their default value, and parameters a leaf layer requires but its Attribute does not describe
+    /// are seeded with `RandN`, except those of custom cells. Layers are built on the first building pass, as for any other Network.
+    ///
+    /// # Errors
+    /// Returns an Err if the definition can not be parsed, or if it describes an invalid Network. See `NodeBuilder::build`.
+    ///
+    /// # Example(s)
+    /// ```
+    /// use anneml::node::network::Network;
+    ///
+    /// let definition = r#"
+    ///     seed = 7
+    ///
+    ///     [root]
+    ///     name = "root"
+    ///
+    ///     [[root.columns]]
+    ///     [[root.columns.children]]
+    ///     name = "encoder"
+    ///     layers = [{ count = 3 }, { count = 2, attribute = { activation = "TanH" } }]
+    ///
+    ///     [[root.columns]]
+    ///     [[root.columns.children]]
+    ///     name = "decoder"
+    ///     layers = [{ count = 2 }]
+    ///     outputs_to_parent = "All"
+    ///     inputs_from_peers = [{ peer = "encoder", range = "All" }]
+    /// "#;
+    ///
+    /// let network = Network::from_config_str(definition).unwrap();
+    /// assert_eq!(7, network.seed());
+    /// assert!(network.find_by_path("root/decoder").is_some());
+    /// ```
+    pub fn from_config_str(definition: &str) -> Result<Network, &'static str> {
+        let config: NetworkConfig = toml::from_str(definition).map_err(|_| "Unable to parse definition.")?;
+        Ok(Network::with_seed(config.root.builder().build()?, config.seed))
+    }
+
+    /// Writes the architecture of the Network as a TOML definition, read back by `Network::from_config_str`.
+    ///
+    /// Every Node is written with its UUID and Edges, so keys that refer to UUIDs remain valid. Weights and other values are left out.
+    ///
+    /// # Errors
+    /// Returns an Err if the architecture can not be written in TOML.
+    ///
+    /// # Example(s)
+    /// ```
+    /// use uuid::Uuid;
+    /// use anneml::node::attribute::Attribute;
+    /// use anneml::node::network::Network;
+    /// use anneml::node::node::{Node, NodeType, VertexKind};
+    ///
+    /// let attribute = Attribute::default();
+    /// let leaf_node = Node::new(Uuid::new_v4(), NodeType::Leaf(attribute.clone(), vec![(attribute.clone(), 2)])).with_name("classifier");
+    /// let network = Network::new(Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, attribute.clone(), leaf_node, attribute.clone())));
+    ///
+    /// let definition = network.to_config().unwrap();
+    /// let copy = Network::from_config_str(&definition).unwrap();
+    /// assert_eq!(definition, copy.to_config().unwrap());
+    /// ```
+    pub fn to_config(&self) -> Result<String, &'static str> {
+        let config = NetworkConfig { seed: self.seed, root: NodeConfig::new(&self.node.read().unwrap()) };
+        toml::to_string(&config).map_err(|_| "Unable to write definition.")
+    }
+
     /// Saves a serialized .annml file.
     pub fn save(&self) {
-        let f_name = format!("{}.annml", self.node().read().unwrap().uuid().to_hyphenated().to_string());
+        let f_name = format!("{}.annml", self.node().read().unwrap().uuid().to_hyphenated());
         let mut file = File::create( f_name).unwrap();
         let _result = file.write_all(&bincode::serialize(&self).unwrap());
     }
@@ -5,30 +5,48 @@
  * (c) Copyright by Christian Potts
  */
 
-use std::sync::{Arc, Mutex };
-use arrayfire::{Array, Dim4, dim4, join_many};
+use std::sync::{Arc, Mutex, MutexGuard};
+use arrayfire::{Array, constant, Dim4, dim4, join_many};
+use rustc_hash::FxHashMap;
 use uuid::Uuid;
-use crate::node::attribute::{Attribute, TensorDescriptor};
-use crate::node::edges::{Edges, NodeRange};
+use crate::node::attribute::{Aggregation, Attribute, TensorDescriptor};
+use crate::node::edges::{Edges, LinkError, LinkType, NodeRange};
+use crate::node::function::{Function, resolve_function};
 use crate::node::layer::Layer;
 use crate::node::mesh::Mesh;
+use crate::node::mode::Mode;
+use crate::node::processor::aggregation::aggregate;
+use crate::node::processor::attention::softmax;
 use crate::node::processor::processor::Processor;
+use crate::node::processor::training::{backpropagate, trainable};
 use crate::node::tensor::Tensor;
-use crate::node::utils::build_array;
+use crate::node::utils::{build_array, random_engine, stream_seed};
+
+/// Layers, columns of children and Edges a new Node is made of.
+type Components = (Vec<Mutex<Layer>>, Vec<Vec<Arc<Mutex<Node>>>>, Mutex<Edges>);
+
+/// Children of a Vertex along with their (column, row) position.
+type Positions = Vec<((usize, usize), Arc<Mutex<Node>>)>;
 
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct Node{
     uuid: Uuid,
+    name: Option<String>,
+    seed: u64,
     mesh: Arc<Mesh>,
-    children: Vec<Vec<Arc<Mutex<Node>>>>
-
+    children: Vec<Vec<Arc<Mutex<Node>>>>,
+    function: Option<(String, String)>,
+    kind: Option<VertexKind>,
+    #[serde(skip)]
+    callable: Mutex<Option<Arc<dyn Function>>>,
 }
 
 impl Node {
     /// A Node is a wrapper for a Mesh.
     ///
-    /// The Node can either be a leaf or vertex, which will determine the role of its child Mesh.
+    /// The Node can either be a leaf, vertex or function, which will determine the role of its child Mesh.
     /// A Node with no children is a leaf- and vertex if it has children.
+    /// A function Node wraps a registered Function instead of learned layers.
     /// Nodes connect between themselves, which form the structure of a Network.
     ///
     /// # Example(s)
@@ -36,7 +54,7 @@ impl Node {
     /// ```
     /// use uuid::Uuid;
     /// use anneml::node::attribute::Attribute;
-    /// use anneml::node::node::{Node, NodeType};
+    /// use anneml::node::node::{Node, NodeType, VertexKind};
     /// // Create a Leaf Node
     /// let attribute = Attribute::default();
     /// let node_type = NodeType::Leaf(attribute.clone(), vec![(attribute.clone(),2),(attribute.clone(),3)]);
@@ -44,16 +62,17 @@ impl Node {
     /// assert_eq!(true, leaf_node.is_leaf_node());
     ///
     /// // Create a Vertex Node
-    /// let node_type = NodeType::Vertex(attribute.clone(),leaf_node, attribute.clone());
+    /// let node_type = NodeType::Vertex(VertexKind::Aggregate, attribute.clone(),leaf_node, attribute.clone());
     /// let vertex_node = Node::new(Uuid::new_v4(), node_type);
     /// assert_eq!(false, vertex_node.is_leaf_node());
     /// ```
     pub fn new(uuid: Uuid, node_type: NodeType) -> Self {
-        let (layers,children, edges) = match node_type {
-            NodeType::Leaf(input, hidden) => { Node::derive_leaf_components(uuid, input, hidden) }
-            NodeType::Vertex(input, child, output) => { Node::derive_vertex_components(uuid, input, output, child) }
+        let ((layers,children, edges), function, kind) = match node_type {
+            NodeType::Leaf(input, hidden) => { (Node::derive_leaf_components(uuid, input, hidden), None, None) }
+            NodeType::Vertex(kind, input, child, output) => { (Node::derive_vertex_components(uuid, input, output, child), None, Some(kind)) }
+            NodeType::Function(name, configuration) => { (Node::derive_function_components(uuid), Some((name, configuration)), None) }
         };
-        Node { uuid, mesh: Arc::new(Mesh::new(layers, edges)), children }
+        Node { uuid, name: None, seed: 0, mesh: Arc::new(Mesh::new(layers, edges)), children, function, kind, callable: Mutex::new(None) }
     }
 
         /// Returns components required for creating a leaf node.
@@ -62,7 +81,7 @@ impl Node {
         /// 1) Layers which are used to define the size of a Node's Mesh.
         /// 2) Empty child array that is the determinant of a Leaf Node.
         /// 3) Edges which connect the Node together.
-        fn derive_leaf_components(uuid: Uuid, input: Attribute, hidden: Vec<(Attribute, u64)>) -> (Vec<Mutex<Layer>>, Vec<Vec<Arc<Mutex<Node>>>>, Mutex<Edges>) {
+        fn derive_leaf_components(uuid: Uuid, input: Attribute, hidden: Vec<(Attribute, u64)>) -> Components {
             (Node::new_node_layers(uuid, input, hidden), vec![], Mutex::new(Edges::new(NodeRange::All)))
         }
 
@@ -72,21 +91,29 @@ impl Node {
         /// 1) Layers which are used to define the size of a Node's Mesh.
         /// 2) Populated child array that is the determinant of a Vertex Node.
         /// 3) Edges which connect the Node together.
-        fn derive_vertex_components(uuid: Uuid, input: Attribute, output: Attribute, child: Node) -> (Vec<Mutex<Layer>>, Vec<Vec<Arc<Mutex<Node>>>>, Mutex<Edges>) {
+        fn derive_vertex_components(uuid: Uuid, input: Attribute, output: Attribute, child: Node) -> Components {
             (Node::new_node_layers(uuid, input, vec![(output, 0)]), vec![vec![Arc::new(Mutex::new(child))]], Mutex::new(Edges::new(NodeRange::Selective(vec![]))))
         }
 
+        /// Returns components required for creating a function node.
+        ///
+        /// A function Node has no children. Its input layer collects the named inputs of the Function, and its output layer holds the Function's outputs.
+        fn derive_function_components(uuid: Uuid) -> Components {
+            (Node::new_node_layers(uuid, Attribute::default(), vec![(Attribute::default(), 0)]), vec![], Mutex::new(Edges::new(NodeRange::All)))
+        }
+
         /// Builds relational layers.
         ///
         /// Creates a vector of layers that may interact with each other through edges.
         /// The first layer is an input layer that is assigned an attribute and a default Tensor.
         /// All other layers are assigned a Tensor with a Arrayfire array entry that is of size (1, count, 1,1), which is the layer size.
+        /// The entries hold zeros until the Node is traversed, so peers reading them before then receive zeros.
         /// We do not assign a Tensor entry to the input layer because the dimensions of the Arrayfire array will change as edges are set.
         fn new_node_layers(uuid: Uuid, attribute: Attribute, hidden: Vec<(Attribute, u64)>) -> Vec<Mutex<Layer>> {
             let mut layers = vec![Mutex::new(Layer::new(attribute, Tensor::default()))];
             layers.splice(1..1, hidden.into_iter().map(|(attr, count)| {
                 let mut hash_map = Tensor::default();
-                if count > 0 { hash_map.insert(uuid.to_string().as_str(), Array::new_empty(Dim4::new(&[1, count, 1, 1]))); };
+                if count > 0 { hash_map.insert(uuid.to_string().as_str(), constant(0.0, Dim4::new(&[1, count, 1, 1]))); };
                 Mutex::new(Layer::new(attr, hash_map))
             }).collect::<Vec<Mutex<Layer>>>());
             layers
@@ -95,7 +122,7 @@ impl Node {
 
     /// Assign a child Node to a parent Vertex node.
     ///
-    /// The passed in child Node is consumed and assigned to the parent.
+    /// The passed in child Node is consumed and assigned to the parent, inheriting the parent's random seed.
     /// The index Option determines if the node will be appended to an existing column of children (Some) or a New Column (None)
     ///
     /// # Errors
@@ -104,6 +131,7 @@ impl Node {
     ///     - And index is Some(`index`) where `index` is within bounds of the current number of columns in the child vector, we will add a child to that column and return ok(())
     ///     - And index is None, we append a new column with the child inside.
     /// 2) If the Node is a Leaf: We receive an Err of "_Unable to add child to leaf._".
+    /// 3) If the Node is a Function: We receive an Err of "_Unable to add child to function node._".
     /// # Panics
     /// Panics if `index` is out of bounds.
     ///
@@ -111,14 +139,14 @@ impl Node {
     /// ```
     /// use uuid::Uuid;
     /// use anneml::node::attribute::Attribute;
-    /// use anneml::node::node::{Node, NodeType};
+    /// use anneml::node::node::{Node, NodeType, VertexKind};
     /// // Add a new column
     /// // Create a Leaf Node
     /// let attribute = Attribute::default();
     /// let node_type = NodeType::Leaf(attribute.clone(), vec![(attribute.clone(),2),(attribute.clone(),3)]);
     /// let leaf_node = Node::new(Uuid::new_v4(), node_type);
     /// // Create a Vertex Node, assign existing Leaf to Vertex Node
-    /// let node_type = NodeType::Vertex(attribute.clone(),leaf_node, attribute.clone());
+    /// let node_type = NodeType::Vertex(VertexKind::Aggregate, attribute.clone(),leaf_node, attribute.clone());
     /// let mut vertex_node = Node::new(Uuid::new_v4(), node_type);
     ///
     /// // Create a NEW Leaf Node
@@ -135,14 +163,14 @@ impl Node {
     /// ```
     /// use uuid::Uuid;
     /// use anneml::node::attribute::Attribute;
-    /// use anneml::node::node::{Node, NodeType};
+    /// use anneml::node::node::{Node, NodeType, VertexKind};
     /// // Append to an existing column
     /// // Create a Leaf Node
     /// let attribute = Attribute::default();
     /// let node_type = NodeType::Leaf(attribute.clone(), vec![(attribute.clone(),2),(attribute.clone(),3)]);
     /// let leaf_node = Node::new(Uuid::new_v4(), node_type);
     /// // Create a Vertex Node, assign existing Leaf to Vertex Node
-    /// let node_type = NodeType::Vertex(attribute.clone(),leaf_node, attribute.clone());
+    /// let node_type = NodeType::Vertex(VertexKind::Aggregate, attribute.clone(),leaf_node, attribute.clone());
     /// let mut vertex_node = Node::new(Uuid::new_v4(), node_type);
     ///
     /// // Create a NEW Leaf Node
@@ -159,7 +187,7 @@ impl Node {
     /// ```
     /// use uuid::Uuid;
     /// use anneml::node::attribute::Attribute;
-    /// use anneml::node::node::{Node, NodeType};
+    /// use anneml::node::node::{Node, NodeType, VertexKind};
     /// // Append add to leaf node
     /// // Create a Leaf Node
     /// let attribute = Attribute::default();
@@ -180,14 +208,14 @@ impl Node {
     /// ```should_panic
     /// use uuid::Uuid;
     /// use anneml::node::attribute::Attribute;
-    /// use anneml::node::node::{Node, NodeType};
+    /// use anneml::node::node::{Node, NodeType, VertexKind};
     /// // Panic
     /// // Create a Leaf Node
     /// let attribute = Attribute::default();
     /// let node_type = NodeType::Leaf(attribute.clone(), vec![(attribute.clone(),2),(attribute.clone(),3)]);
     /// let leaf_node = Node::new(Uuid::new_v4(), node_type);
     /// // Create a Vertex Node, assign existing Leaf to Vertex Node
-    /// let node_type = NodeType::Vertex(attribute.clone(),leaf_node, attribute.clone());
+    /// let node_type = NodeType::Vertex(VertexKind::Aggregate, attribute.clone(),leaf_node, attribute.clone());
     /// let mut vertex_node = Node::new(Uuid::new_v4(), node_type);
     ///
     /// // Create a NEW Leaf Node
@@ -198,13 +226,16 @@ impl Node {
     /// let result = vertex_node.add_child_to_parent(new_leaf_node, Some(1));
     /// assert!(false);
     /// ```
-    pub fn add_child_to_parent(&mut self, child: Node, index: Option<usize>) -> Result<(), &'static str> {
+    pub fn add_child_to_parent(&mut self, mut child: Node, index: Option<usize>) -> Result<(), &'static str> {
+        if self.is_function_node() { return Err("Unable to add child to function node.") }
         if self.is_leaf_node() { return Err("Unable to add child to leaf.") }
+        child.set_seed(self.seed);
         let child = Arc::new(Mutex::new(child));
         match index {
             None => { self.children.push(vec![child]); }
             Some(index) => { self.children[index].push(child); }
         }
+        self.invalidate_aggregation_weights();
         Ok(())
     }
 
@@ -218,6 +249,7 @@ impl Node {
     ///     - And indices: &[(usize, usize)] _do not_ exceed the depth of the network, a Result<Arc<Mutex<Node>> will be returned.
     ///     - And indices: &[(usize, usize)] _does_ exceed the depth of the network, we panic.
     /// 2) If the Node is a Leaf: We receive an Err of "_Unable to index into leaf node._".
+    /// 3) If the Node is a Function: We receive an Err of "_Unable to index into function node._".
     /// # Panics
     /// Panics if recursive `index` is out of bounds.
     ///
@@ -225,14 +257,14 @@ impl Node {
     /// ```
     /// use uuid::Uuid;
     /// use anneml::node::attribute::Attribute;
-    /// use anneml::node::node::{Node, NodeType};
+    /// use anneml::node::node::{Node, NodeType, VertexKind};
     /// // Index into a Vertex Nodes' child
     /// // Create a Leaf Node
     /// let attribute = Attribute::default();
     /// let node_type = NodeType::Leaf(attribute.clone(), vec![(attribute.clone(),2),(attribute.clone(),3)]);
     /// let leaf_node = Node::new(Uuid::new_v4(), node_type);
     /// // Create a Vertex Node, assign existing Leaf to Vertex Node
-    /// let node_type = NodeType::Vertex(attribute.clone(),leaf_node, attribute.clone());
+    /// let node_type = NodeType::Vertex(VertexKind::Aggregate, attribute.clone(),leaf_node, attribute.clone());
     /// let mut vertex_node = Node::new(Uuid::new_v4(), node_type);
     ///
     /// // Get Leaf Node &reference
@@ -243,7 +275,7 @@ impl Node {
     /// ```
     /// use uuid::Uuid;
     /// use anneml::node::attribute::Attribute;
-    /// use anneml::node::node::{Node, NodeType};
+    /// use anneml::node::node::{Node, NodeType, VertexKind};
     /// // Index into a Leaf Node
     /// // Create a Leaf Node
     /// let attribute = Attribute::default();
@@ -258,14 +290,14 @@ impl Node {
     /// ```should_panic
     /// use uuid::Uuid;
     /// use anneml::node::attribute::Attribute;
-    /// use anneml::node::node::{Node, NodeType};
+    /// use anneml::node::node::{Node, NodeType, VertexKind};
     /// // Index into a Vertex Nodes' child
     /// // Create a Leaf Node
     /// let attribute = Attribute::default();
     /// let node_type = NodeType::Leaf(attribute.clone(), vec![(attribute.clone(),2),(attribute.clone(),3)]);
     /// let leaf_node = Node::new(Uuid::new_v4(), node_type);
     /// // Create a Vertex Node, assign existing Leaf to Vertex Node
-    /// let node_type = NodeType::Vertex(attribute.clone(),leaf_node, attribute.clone());
+    /// let node_type = NodeType::Vertex(VertexKind::Aggregate, attribute.clone(),leaf_node, attribute.clone());
     /// let mut vertex_node = Node::new(Uuid::new_v4(), node_type);
     ///
     /// // Get Leaf Node &reference (should panic)
@@ -273,41 +305,780 @@ impl Node {
     /// assert!(false);
     /// ```
     pub fn index_into_node(&self, indices: &[(usize, usize)]) -> Result<Arc<Mutex<Node>>, &'static str> {
+        if self.is_function_node() { return Err("Unable to index into function node."); }
         if self.is_leaf_node() { return Err("Unable to index into leaf node."); }
         let node = self.children[indices[0].0][indices[0].1].clone();
         if indices[1..].is_empty() { Ok(node) } else { node.lock().unwrap().index_into_node(&indices[1..]) }
     }
+
+    /// Removes a child from a parent Vertex node, returning the removed subtree.
+    ///
+    /// A column left without children is removed, shifting the following columns.
+    /// Peer links of the remaining children are rewritten to the shifted positions, and links to the removed child are dropped.
+    /// The removed child loses its own peer links, as they refer to its former siblings, and its outputs are removed from the Vertex.
+    ///
+    /// # Errors
+    /// Returns an Err if the Node is not a Vertex, if `index` is out of bounds, if the child is the last one of the Vertex,
+    /// or if the child is still referenced elsewhere.
+    ///
+    /// # Example(s)
+    /// ```
+    /// use uuid::Uuid;
+    /// use anneml::node::attribute::Attribute;
+    /// use anneml::node::node::{Node, NodeType, VertexKind};
+    ///
+    /// let attribute = Attribute::default();
+    /// let leaf = || Node::new(Uuid::new_v4(), NodeType::Leaf(attribute.clone(), vec![(attribute.clone(), 2)]));
+    /// let mut vertex_node = Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, attribute.clone(), leaf(), attribute.clone()));
+    /// vertex_node.add_child_to_parent(leaf(), None).unwrap();
+    ///
+    /// let removed = vertex_node.remove_child((0,0)).unwrap();
+    /// assert!(removed.is_leaf_node());
+    /// assert_eq!(1, vertex_node.children().len());
+    /// ```
+    pub fn remove_child(&mut self, index: (usize, usize)) -> Result<Node, &'static str> {
+        self.child_at(index)?;
+        if self.children.iter().flatten().count() == 1 { return Err("Unable to remove the last child of a vertex.") }
+        if Arc::strong_count(&self.children[index.0][index.1]) > 1 { return Err("Child is still referenced.") }
+        let positions = self.positions();
+        let child = self.children[index.0].remove(index.1);
+        if self.children[index.0].is_empty() { self.children.remove(index.0); }
+        self.rewrite_peer_links(positions);
+        let child = Arc::try_unwrap(child).map_err(|_| "Child is still referenced.")?.into_inner().unwrap();
+        child.mesh().edges().input_from_peer_output.clear();
+        self.remove_child_outputs(&child);
+        self.invalidate_aggregation_weights();
+        Ok(child)
+    }
+
+    /// Replaces a child of a parent Vertex node in place, returning the replaced subtree.
+    ///
+    /// The new child takes over the position, so peer links to that position now refer to it. It inherits the parent's random seed.
+    /// The new child also takes over the Edges of the replaced child, keeping its links to the parent and peers.
+    /// The replaced child is handed the Edges of the new child, without peer links as they would refer to its former siblings,
+    /// and its outputs are removed from the Vertex.
+    ///
+    /// # Errors
+    /// Returns an Err if the Node is not a Vertex, if `index` is out of bounds, or if the replaced child is still referenced elsewhere.
+    ///
+    /// # Example(s)
+    /// ```
+    /// use uuid::Uuid;
+    /// use anneml::node::attribute::Attribute;
+    /// use anneml::node::node::{Node, NodeType, VertexKind};
+    ///
+    /// let attribute = Attribute::default();
+    /// let leaf = Node::new(Uuid::new_v4(), NodeType::Leaf(attribute.clone(), vec![(attribute.clone(), 2)]));
+    /// let mut vertex_node = Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, attribute.clone(), leaf, attribute.clone()));
+    ///
+    /// let replacement = Node::new(Uuid::new_v4(), NodeType::Leaf(attribute.clone(), vec![(attribute.clone(), 3)]));
+    /// let replaced = vertex_node.replace_child((0,0), replacement).unwrap();
+    /// assert!(replaced.is_leaf_node());
+    /// ```
+    pub fn replace_child(&mut self, index: (usize, usize), mut child: Node) -> Result<Node, &'static str> {
+        self.child_at(index)?;
+        if Arc::strong_count(&self.children[index.0][index.1]) > 1 { return Err("Child is still referenced.") }
+        child.set_seed(self.seed);
+        std::mem::swap(&mut *self.children[index.0][index.1].lock().unwrap().mesh().edges(), &mut *child.mesh().edges());
+        let replaced = std::mem::replace(&mut self.children[index.0][index.1], Arc::new(Mutex::new(child)));
+        let replaced = Arc::try_unwrap(replaced).map_err(|_| "Child is still referenced.")?.into_inner().unwrap();
+        replaced.mesh().edges().input_from_peer_output.clear();
+        self.remove_child_outputs(&replaced);
+        self.invalidate_aggregation_weights();
+        Ok(replaced)
+    }
+
+    /// Moves a child of a parent Vertex node to another position, within its column or to another one.
+    ///
+    /// The child is first taken out of its column, a column left without children being removed.
+    /// It is then inserted at row `to.1` of column `to.0`, positions referring to the grid without the child.
+    /// A `to.0` equal to the number of columns appends a new column.
+    /// Peer links of every child, including links to the moved child, are rewritten to the new positions.
+    ///
+    /// # Errors
+    /// Returns an Err if the Node is not a Vertex, or if `from` or `to` is out of bounds.
+    ///
+    /// # Example(s)
+    /// ```
+    /// use uuid::Uuid;
+    /// use anneml::node::attribute::Attribute;
+    /// use anneml::node::node::{Node, NodeType, VertexKind};
+    ///
+    /// let attribute = Attribute::default();
+    /// let leaf = || Node::new(Uuid::new_v4(), NodeType::Leaf(attribute.clone(), vec![(attribute.clone(), 2)]));
+    /// let mut vertex_node = Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, attribute.clone(), leaf(), attribute.clone()));
+    /// vertex_node.add_child_to_parent(leaf(), None).unwrap();
+    ///
+    /// // Move the second column's child below the first one.
+    /// vertex_node.move_child((1,0), (0,1)).unwrap();
+    /// assert_eq!(1, vertex_node.children().len());
+    /// assert_eq!(2, vertex_node.children()[0].len());
+    /// ```
+    pub fn move_child(&mut self, from: (usize, usize), to: (usize, usize)) -> Result<(), &'static str> {
+        self.child_at(from)?;
+        let mut rows = self.children.iter().map(|column| column.len()).collect::<Vec<usize>>();
+        rows[from.0] -= 1;
+        if rows[from.0] == 0 { rows.remove(from.0); }
+        if !((to.0 == rows.len() && to.1 == 0) || rows.get(to.0).is_some_and(|rows| to.1 <= *rows)) { return Err("Child index out of bounds.") }
+        let positions = self.positions();
+        let child = self.children[from.0].remove(from.1);
+        if self.children[from.0].is_empty() { self.children.remove(from.0); }
+        if to.0 == self.children.len() { self.children.push(vec![child]); } else { self.children[to.0].insert(to.1, child); }
+        self.rewrite_peer_links(positions);
+        self.invalidate_aggregation_weights();
+        Ok(())
+    }
+
+    /// Restricts the inputs the Node receives from its parent to `node_range`.
+    ///
+    /// # Errors
+    /// Returns a LinkError if a Selective or Mapped NodeRange lists a system key or the same key twice, or selects no column, or if the Edges reject the link.
+    ///
+    /// # Example(s)
+    /// ```
+    /// use uuid::Uuid;
+    /// use anneml::node::attribute::Attribute;
+    /// use anneml::node::edges::{LinkError, NodeRange};
+    /// use anneml::node::node::{Node, NodeType};
+    ///
+    /// let attribute = Attribute::default();
+    /// let leaf_node = Node::new(Uuid::new_v4(), NodeType::Leaf(attribute.clone(), vec![(attribute.clone(), 2)]));
+    /// leaf_node.link_input_from_parent(NodeRange::Selective(vec!["input".to_string()])).unwrap();
+    /// assert_eq!(Err(LinkError::ReservedKey("_SYSTEM_VALUES".to_string())), leaf_node.link_input_from_parent(NodeRange::Selective(vec!["_SYSTEM_VALUES".to_string()])));
+    /// ```
+    pub fn link_input_from_parent(&self, node_range: NodeRange) -> Result<(), LinkError> {
+        Node::check_keys(&node_range, None)?;
+        self.mesh.edges().link(LinkType::InputsFromParent, node_range).map_err(LinkError::Rejected)?;
+        Ok(())
+    }
+
+    /// Restricts the outputs the Node passes to its parent to `node_range`.
+    ///
+    /// # Errors
+    /// Returns a LinkError if a Selective or Mapped NodeRange lists a system key or the same key twice, or selects no column, or if the Node is a leaf and a key selects none of its outputs, or if the Edges reject the link.
+    ///
+    /// # Example(s)
+    /// ```
+    /// use uuid::Uuid;
+    /// use anneml::node::attribute::Attribute;
+    /// use anneml::node::edges::{LinkError, NodeRange};
+    /// use anneml::node::node::{Node, NodeType};
+    ///
+    /// let attribute = Attribute::default();
+    /// let uuid = Uuid::new_v4();
+    /// let leaf_node = Node::new(uuid, NodeType::Leaf(attribute.clone(), vec![(attribute.clone(), 2)]));
+    /// leaf_node.link_output_to_parent(NodeRange::Selective(vec![uuid.to_string()])).unwrap();
+    /// assert_eq!(Err(LinkError::UnknownKey("missing".to_string())), leaf_node.link_output_to_parent(NodeRange::Selective(vec!["missing".to_string()])));
+    /// ```
+    pub fn link_output_to_parent(&self, node_range: NodeRange) -> Result<(), LinkError> {
+        Node::check_keys(&node_range, self.leaf_outputs().as_deref())?;
+        self.mesh.edges().link(LinkType::OutputsToParent, node_range).map_err(LinkError::Rejected)?;
+        Ok(())
+    }
+
+    /// Links the outputs of the child at `from` to the inputs of the child at `to`, both positions being (column, row) in this vertex.
+    ///
+    /// The child at `to` reads the outputs of the current pass if the child at `from` is traversed before it, and those of the previous pass otherwise.
+    ///
+    /// # Errors
+    /// Returns a LinkError if:
+    /// 1) The Node is not a vertex, either position holds no child, or both positions are the same.
+    /// 2) The child at `to` already reads the outputs of the child at `from`.
+    /// 3) A Selective or Mapped NodeRange lists a system key or the same key twice or selects no column, or the child at `from` is a leaf and a key selects none of its outputs.
+    /// 4) The Edges of the child at `to` reject the link.
+    ///
+    /// # Example(s)
+    /// ```
+    /// use uuid::Uuid;
+    /// use anneml::node::attribute::Attribute;
+    /// use anneml::node::edges::{LinkError, NodeRange};
+    /// use anneml::node::node::{Node, NodeType, VertexKind};
+    ///
+    /// let attribute = Attribute::default();
+    /// let leaf = || Node::new(Uuid::new_v4(), NodeType::Leaf(attribute.clone(), vec![(attribute.clone(), 2)]));
+    /// let mut vertex_node = Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, attribute.clone(), leaf(), attribute.clone()));
+    /// vertex_node.add_child_to_parent(leaf(), None).unwrap();
+    ///
+    /// vertex_node.link_peer((0,0), (1,0), NodeRange::All).unwrap();
+    /// assert_eq!(Err(LinkError::DuplicateLink((0,0))), vertex_node.link_peer((0,0), (1,0), NodeRange::All));
+    /// assert_eq!(Err(LinkError::OutOfBounds((2,0))), vertex_node.link_peer((2,0), (1,0), NodeRange::All));
+    /// ```
+    pub fn link_peer(&self, from: (usize, usize), to: (usize, usize), node_range: NodeRange) -> Result<(), LinkError> {
+        if self.children.is_empty() { return Err(LinkError::NotVertex) }
+        let child = |(x, y): (usize, usize)| self.children.get(x).and_then(|column| column.get(y)).ok_or(LinkError::OutOfBounds((x, y)));
+        let (source, target) = (child(from)?, child(to)?);
+        if from == to { return Err(LinkError::SelfLink) }
+        let target = target.lock().unwrap();
+        if target.mesh.edges().input_from_peer_output.iter().any(|(position, _)| *position == from) { return Err(LinkError::DuplicateLink(from)) }
+        Node::check_keys(&node_range, source.lock().unwrap().leaf_outputs().as_deref())?;
+        target.mesh.edges().link(LinkType::InputsFromPeerOutputs(from), node_range).map_err(LinkError::Rejected)?;
+        Ok(())
+    }
+
+    /// Returns the output keys of a leaf, known before it is traversed unless its size is computed when built.
+    fn leaf_outputs(&self) -> Option<Vec<String>> {
+        if !self.is_leaf_node() { return None }
+        let outputs = self.mesh.layers().last().unwrap().lock().unwrap().tensor.hash_map.keys().filter(|key| !key.contains("_SYSTEM")).cloned().collect::<Vec<String>>();
+        if outputs.is_empty() { None } else { Some(outputs) }
+    }
+
+    /// Checks the keys of a Selective or Mapped NodeRange, and that they select some of `outputs` if known.
+    ///
+    /// The keys of a Mapped NodeRange are its patterns, and the keys it passes on are its aliases.
+    fn check_keys(node_range: &NodeRange, outputs: Option<&[String]>) -> Result<(), LinkError> {
+        match node_range {
+            NodeRange::All => {}
+            NodeRange::Selective(key_chain) => {
+                for (index, key) in key_chain.iter().enumerate() {
+                    if key.contains("_SYSTEM") { return Err(LinkError::ReservedKey(key.clone())) }
+                    if key_chain[..index].contains(key) { return Err(LinkError::DuplicateKey(key.clone())) }
+                    if outputs.is_some_and(|outputs| !outputs.contains(key)) { return Err(LinkError::UnknownKey(key.clone())) }
+                }
+            }
+            NodeRange::Mapped(key_maps) => {
+                for (index, key_map) in key_maps.iter().enumerate() {
+                    let pattern = key_map.pattern().to_string();
+                    if let Some(key) = std::iter::once(key_map.pattern()).chain(key_map.alias()).find(|key| key.contains("_SYSTEM")) { return Err(LinkError::ReservedKey(key.to_string())) }
+                    if let Some(alias) = key_map.alias().filter(|alias| key_maps[..index].iter().any(|other| other.alias() == Some(alias))) { return Err(LinkError::DuplicateKey(alias.to_string())) }
+                    if key_map.columns().is_some_and(|columns| columns.is_empty()) { return Err(LinkError::EmptyColumns(pattern)) }
+                    if outputs.is_some_and(|outputs| !outputs.iter().any(|key| key_map.matches(key))) { return Err(LinkError::UnknownKey(pattern)) }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that `replacement` can take the place of the Node without changing how it is connected.
+    ///
+    /// The replacement has to provide every output entry of the Node with the same size, so the parent and peers keep receiving them.
+    /// Once the Node has received inputs, the replacement may only expect input entries the Node receives, with the same size.
+    ///
+    /// # Errors
+    /// Returns an Err describing the first incompatibility.
+    pub(crate) fn check_replacement(&self, replacement: &Node) -> Result<(), &'static str> {
+        let entries = |layer: &Mutex<Layer>| layer.lock().unwrap().tensor.hash_map.iter()
+            .filter(|(key, _)| !key.contains("_SYSTEM")).map(|(key, value)| (key.clone(), value.dims()[1])).collect::<Vec<(String, u64)>>();
+        let outputs = entries(self.mesh.layers().last().unwrap());
+        let replacement_outputs = entries(replacement.mesh.layers().last().unwrap());
+        if outputs.iter().any(|output| !replacement_outputs.contains(output)) { return Err("Replacement does not provide the outputs of the replaced node.") }
+        let inputs = entries(&self.mesh.layers()[0]);
+        let replacement_inputs = entries(&replacement.mesh.layers()[0]);
+        if !inputs.is_empty() && replacement_inputs.iter().any(|input| !inputs.contains(input)) { return Err("Replacement expects inputs the replaced node does not receive.") }
+        Ok(())
+    }
+
+    /// Checks that `index` is the position of a child.
+    ///
+    /// # Errors
+    /// Returns an Err if the Node is not a Vertex, or if `index` is out of bounds.
+    fn child_at(&self, index: (usize, usize)) -> Result<(), &'static str> {
+        if self.is_function_node() { return Err("Unable to edit children of function node.") }
+        if self.is_leaf_node() { return Err("Unable to edit children of leaf.") }
+        self.children.get(index.0).and_then(|column| column.get(index.1)).map(|_| ()).ok_or("Child index out of bounds.")
+    }
+
+    /// Removes the entries a former child passed to the output layer and Tensor.
+    fn remove_child_outputs(&self, child: &Node) {
+        let mut output = self.mesh.layers()[1].lock().unwrap();
+        let keys = Node::output_keys(&output, child);
+        self.remove_outputs(&mut output, &[keys]);
+    }
+
+    /// Returns the position of every child.
+    fn positions(&self) -> Positions {
+        self.children.iter().enumerate()
+            .flat_map(|(x, column)| column.iter().enumerate().map(move |(y, child)| ((x, y), child.clone()))).collect()
+    }
+
+    /// Rewrites the peer links of every child from the `previous` positions of the children to their current ones.
+    ///
+    /// Links to children that are no longer in the grid are dropped.
+    fn rewrite_peer_links(&self, previous: Positions) {
+        let current = self.positions();
+        let remap = |position: (usize, usize)| previous.iter().find(|(before, _)| *before == position)
+            .and_then(|(_, node)| current.iter().find(|(_, after)| Arc::ptr_eq(node, after)).map(|(after, _)| *after));
+        current.iter().for_each(|(_, child)| {
+            let child = child.lock().unwrap();
+            let mesh = child.mesh();
+            let mut edges = mesh.edges();
+            edges.input_from_peer_output = edges.input_from_peer_output.drain(..)
+                .filter_map(|(position, range)| remap(position).map(|position| (position, range))).collect();
+        });
+    }
     
     /// Instantiates values for a leaf node.
     ///
+    /// Size layers whose CellType determines their own size.
     /// Create a _SYSTEM_VALUES entry in the Nodes Tensor. _SYSTEM_VALUES tracks the values of each layer.
     /// Build layers within Nodes Mesh.
-    fn init_leaf(&self) {
+    ///
+    /// # Errors
+    /// Returns an Err if a layer could not be sized or built from its Attribute's TensorDescriptors.
+    fn init_leaf(&self) -> Result<(), &'static str> {
+        self.shape_layers()?;
         let topology = self.mesh().topology();
-        self.mesh().tensor().insert("_SYSTEM_VALUES", build_array(&TensorDescriptor::Const(0.0), dim4!(1,*topology.iter().max().unwrap() as u64,topology.len() as u64,1)));
-        self.mesh().layers().iter().enumerate().for_each(|(index, layer)| layer.lock().unwrap().build(index, &topology));
+        let dense = self.mesh().layers().iter().map(|layer| layer.lock().unwrap().is_dense()).collect::<Vec<bool>>();
+        let engine = random_engine(stream_seed(self.seed, self.uuid.as_u128(), topology.len() as u64));
+        self.mesh().tensor().insert("_SYSTEM_VALUES", build_array(&TensorDescriptor::Const(0.0), dim4!(1,*topology.iter().max().unwrap() as u64,topology.len() as u64,1), &engine)?);
+        self.mesh().layers().iter().enumerate().try_for_each(|(index, layer)| layer.lock().unwrap().build(index, &topology, &dense, stream_seed(self.seed, self.uuid.as_u128(), index as u64)))
+    }
+
+    /// Propagates layer sizes through the Mesh.
+    ///
+    /// Layers whose CellType computes its size from the previous layer (i.e. convolutions) or from an input entry (i.e. embeddings) have their size entry replaced.
+    ///
+    /// # Errors
+    /// Returns an Err if the input layer is not dense, if an input entry is missing or if a layer size can not be computed from its source.
+    fn shape_layers(&self) -> Result<(), &'static str> {
+        if !self.mesh().layers()[0].lock().unwrap().is_dense() { return Err("Input layer must be a dense cell.") }
+        for index in 1..self.mesh().layers().len() {
+            let key = self.mesh().layers()[index].lock().unwrap().attribute.cell_type().input_key().map(str::to_string);
+            let width = match key {
+                Some(key) => { self.mesh().layers()[0].lock().unwrap().tensor.hash_map.get(&key).ok_or("Embedding key is not an input of the Node.")?.dims()[1] as usize }
+                None => { self.mesh().topology()[index - 1] }
+            };
+            let mut layer = self.mesh.layers()[index].lock().unwrap();
+            if let Some(width) = layer.output_width(width)? {
+                layer.tensor.insert(self.uuid.to_string().as_str(), constant(0.0, dim4!(1, width as u64, 1, 1)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Drops the weights of a `WeightedMean` Aggregation, so they are rebuilt for the current children on the next pass.
+    fn invalidate_aggregation_weights(&self) {
+        self.mesh.layers()[1].lock().unwrap().tensor.hash_map.remove("_SYSTEM_AGGREGATION_WEIGHTS");
+    }
+
+    /// Combines the outputs of the children with the Aggregation of the output Attribute.
+    ///
+    /// Unless the children are concatenated, their entries in the output layer and Tensor are replaced by a single entry keyed by the vertex's UUID.
+    /// Missing `WeightedMean` weights are built for the children with outputs.
+    ///
+    /// # Errors
+    /// Returns an Err if the weights can not be built, or if the outputs of the children can not be aggregated.
+    fn aggregate_outputs(&self) -> Result<(), &'static str> {
+        let mut output = self.mesh.layers()[1].lock().unwrap();
+        let aggregation = output.attribute.aggregation().clone();
+        if aggregation == Aggregation::Concatenate { return Ok(()) }
+        let keys = self.children.iter().flatten().map(|child| Node::output_keys(&output, &child.lock().unwrap())).collect::<Vec<Vec<String>>>();
+        let arrays = keys.iter().filter(|keys| !keys.is_empty()).map(|keys| Node::joined_output(&output, keys)).collect::<Vec<Array<f64>>>();
+        if aggregation == Aggregation::WeightedMean && !output.tensor.hash_map.contains_key("_SYSTEM_AGGREGATION_WEIGHTS") {
+            let engine = random_engine(stream_seed(self.seed, self.uuid.as_u128(), 1));
+            let description = output.attribute.description_or("_SYSTEM_AGGREGATION_WEIGHTS", &TensorDescriptor::Const(1.0)).clone();
+            output.tensor.insert("_SYSTEM_AGGREGATION_WEIGHTS", build_array(&description, dim4!(arrays.len() as u64, 1, 1, 1), &engine)?);
+        }
+        let aggregated = aggregate(&arrays, &aggregation, output.tensor.hash_map.get("_SYSTEM_AGGREGATION_WEIGHTS"))?;
+        self.replace_outputs(&mut output, &keys, aggregated);
+        Ok(())
+    }
+
+    /// Traverses a child once it received the outputs of its peers, then merges its outputs into the output layer.
+    ///
+    /// Peers traversed before the child pass it their outputs of the current pass, peers traversed after it those of the previous pass.
+    fn traverse_child(&self, child: &MutexGuard<Node>, build: bool, mode: Mode) -> Result<(), &'static str> {
+        self.assign_sibling_inputs(&self.children, child);
+        self.assign_children_inputs(self.mesh().layers(), child, build, mode)
+    }
+
+    /// Traverses a mixture-of-experts vertex.
+    ///
+    /// The first child is the gate, whose outputs are turned into one weight per remaining child (expert) by a softmax.
+    /// Only the experts selected by the gate's weights are traversed, see `Selection`.
+    /// Passes that build the Node traverse every expert, so each of them is built.
+    /// The output is the mean of the selected expert outputs weighted by the gate, keyed by the vertex's UUID.
+    ///
+    /// # Errors
+    /// Returns an Err if there is no expert to select, or if the gate outputs do not match the number of experts.
+    fn traverse_gated(&self, build: bool, mode: Mode, selection: &Selection) -> Result<(), &'static str> {
+        let children = self.children.iter().flatten().collect::<Vec<&Arc<Mutex<Node>>>>();
+        let (gate, experts) = children.split_first().ok_or("Gated vertex requires a gate.")?;
+        let top_k = match selection {
+            Selection::All => { experts.len() }
+            Selection::TopK(top_k) => { *top_k }
+        };
+        if experts.is_empty() || top_k == 0 { return Err("Gated vertex requires experts to select.") }
+        self.traverse_child(&gate.lock().unwrap(), build, mode)?;
+        let scores = {
+            let output = self.mesh.layers()[1].lock().unwrap();
+            Node::joined_output(&output, &Node::output_keys(&output, &gate.lock().unwrap()))
+        };
+        if scores.elements() != experts.len() { return Err("Gate outputs do not match the number of experts.") }
+        let mut weights = vec![0.0f64; experts.len()];
+        softmax(&scores).host(&mut weights);
+        let mut selected = (0..experts.len()).collect::<Vec<usize>>();
+        selected.sort_by(|a, b| weights[*b].total_cmp(&weights[*a]));
+        selected.truncate(top_k);
+
+        let traversed = children.iter().enumerate().filter(|(i, _)| *i == 0 || build || selected.contains(&(i - 1))).map(|(_, child)| *child).collect::<Vec<&Arc<Mutex<Node>>>>();
+        traversed[1..].iter().try_for_each(|child| self.traverse_child(&child.lock().unwrap(), build, mode))?;
+        traversed.iter().for_each(|child| self.assign_sibling_inputs(&self.children, &child.lock().unwrap()));
+        traversed.iter().for_each(|child| self.assign_parent_outputs(self.mesh().tensor(), &child.lock().unwrap()));
+
+        let mut output = self.mesh.layers()[1].lock().unwrap();
+        let keys = children.iter().map(|child| Node::output_keys(&output, &child.lock().unwrap())).collect::<Vec<Vec<String>>>();
+        let arrays = selected.iter().map(|i| Node::joined_output(&output, &keys[i + 1])).collect::<Vec<Array<f64>>>();
+        let selected_weights = Array::new(&selected.iter().map(|i| weights[*i]).collect::<Vec<f64>>(), dim4!(selected.len() as u64, 1, 1, 1));
+        let combined = aggregate(&arrays, &Aggregation::WeightedMean, Some(&selected_weights))?;
+        self.replace_outputs(&mut output, &keys, combined);
+        Ok(())
+    }
+
+    /// Traverses a routing vertex.
+    ///
+    /// The first column of children holds the router, whose first Node outputs one score per remaining column (route).
+    /// Only the routes selected by the router's scores are traversed, see `Routing`.
+    /// Passes that build the Node traverse every route, so each of them is built.
+    /// The outputs of the router and of the routes that were not taken are removed from the vertex's outputs, and the routes taken are recorded.
+    ///
+    /// # Errors
+    /// Returns an Err if there is no route, or if the router outputs do not match the number of routes.
+    fn traverse_routed(&self, build: bool, mode: Mode, routing: &Routing) -> Result<(), &'static str> {
+        if self.children.len() < 2 { return Err("Routing vertex requires routes.") }
+        self.children[0].iter().try_for_each(|child| self.traverse_child(&child.lock().unwrap(), build, mode))?;
+        let scores = {
+            let output = self.mesh.layers()[1].lock().unwrap();
+            Node::joined_output(&output, &Node::output_keys(&output, &self.children[0][0].lock().unwrap()))
+        };
+        let routes = self.children.len() - 1;
+        if scores.elements() != routes { return Err("Router outputs do not match the number of routes.") }
+        let mut host = vec![0.0f64; routes];
+        scores.host(&mut host);
+        let taken = match routing {
+            Routing::Best => { vec![(0..routes).fold(0, |best, route| if host[route] > host[best] { route } else { best })] }
+            Routing::Threshold(threshold) => { (0..routes).filter(|route| host[*route] >= *threshold).collect() }
+        };
+        let mask = (0..routes).map(|route| if taken.contains(&route) { 1.0 } else { 0.0 }).collect::<Vec<f64>>();
+        self.mesh().tensor().insert("_SYSTEM_ROUTE", Array::new(&mask, dim4!(1, routes as u64, 1, 1)));
+
+        let traversed = self.children.iter().enumerate().filter(|(column, _)| *column == 0 || build || taken.contains(&(column - 1)))
+            .flat_map(|(_, column)| column.iter()).collect::<Vec<&Arc<Mutex<Node>>>>();
+        traversed.iter().skip(self.children[0].len()).try_for_each(|child| self.traverse_child(&child.lock().unwrap(), build, mode))?;
+        traversed.iter().for_each(|child| self.assign_sibling_inputs(&self.children, &child.lock().unwrap()));
+        traversed.iter().for_each(|child| self.assign_parent_outputs(self.mesh().tensor(), &child.lock().unwrap()));
+
+        let mut output = self.mesh.layers()[1].lock().unwrap();
+        let removed = self.children.iter().enumerate().filter(|(column, _)| *column == 0 || !taken.contains(&(column - 1)))
+            .flat_map(|(_, column)| column.iter()).map(|child| Node::output_keys(&output, &child.lock().unwrap())).collect::<Vec<Vec<String>>>();
+        self.remove_outputs(&mut output, &removed);
+        Ok(())
+    }
+
+    /// Returns the child columns taken by a routing vertex on its last pass.
+    ///
+    /// Returns None if the Node is not a routing vertex, or has not been traversed yet.
+    ///
+    /// # Example(s)
+    /// ```
+    /// use uuid::Uuid;
+    /// use anneml::node::attribute::Attribute;
+    /// use anneml::node::node::{Node, NodeType};
+    ///
+    /// let attribute = Attribute::default();
+    /// let node = Node::new(Uuid::new_v4(), NodeType::Leaf(attribute.clone(), vec![(attribute, 2)]));
+    /// assert_eq!(None, node.route());
+    /// ```
+    pub fn route(&self) -> Option<Vec<usize>> {
+        let tensor = self.mesh.tensor();
+        let mask = tensor.hash_map.get("_SYSTEM_ROUTE")?;
+        let mut host = vec![0.0; mask.elements()];
+        mask.host(&mut host);
+        Some(host.iter().enumerate().filter(|(_, taken)| **taken > 0.0).map(|(route, _)| route + 1).collect())
+    }
+
+    /// Returns the sorted keys of the entries a child passed to the output layer.
+    fn output_keys(output: &Layer, child: &Node) -> Vec<String> {
+        let selected = child.mesh().edges().output_to_parent.select(&child.mesh().layers().last().unwrap().lock().unwrap().tensor);
+        let mut keys = selected.into_iter().map(|(key, _)| key)
+            .filter(|key| !key.contains("_SYSTEM") && output.tensor.hash_map.contains_key(key)).collect::<Vec<String>>();
+        keys.sort();
+        keys
+    }
+
+    /// Joins the entries of the output layer under `keys` into a single row.
+    fn joined_output(output: &Layer, keys: &[String]) -> Array<f64> {
+        join_many(1, keys.iter().map(|key| output.tensor.hash_map.get(key).unwrap()).collect::<Vec<&Array<f64>>>())
+    }
+
+    /// Removes the entries of the children from the output layer and Tensor.
+    fn remove_outputs(&self, output: &mut Layer, keys: &[Vec<String>]) {
+        keys.iter().flatten().for_each(|key| { output.tensor.hash_map.remove(key); self.mesh().tensor().hash_map.remove(key); });
+    }
+
+    /// Replaces the entries of the children in the output layer and Tensor with a single entry keyed by the vertex's UUID.
+    fn replace_outputs(&self, output: &mut Layer, keys: &[Vec<String>], value: Array<f64>) {
+        self.remove_outputs(output, keys);
+        output.tensor.insert(self.uuid.to_string().as_str(), value.clone());
+        self.mesh().tensor().insert(self.uuid.to_string().as_str(), value);
+    }
+
+    /// Returns whether the Node has been built by a pass, every leaf under it holding its values.
+    pub(crate) fn is_built(&self) -> bool {
+        if self.is_function_node() { return true }
+        if self.is_leaf_node() { return self.mesh.tensor().hash_map.contains_key("_SYSTEM_VALUES") }
+        self.children.iter().flatten().all(|child| child.lock().unwrap().is_built())
     }
 
     /// Returns whether a Node is a Leaf.
     ///
-    /// A Node is a leaf if it does not have any children.
+    /// A Node is a leaf if it does not have any children, and does not wrap a Function.
     pub fn is_leaf_node(&self) -> bool {
-        self.children.is_empty()
+        self.children.is_empty() && self.function.is_none()
+    }
+
+    /// Returns whether a Node wraps a Function.
+    pub fn is_function_node(&self) -> bool {
+        self.function.is_some()
+    }
+
+    /// Returns the Function of the Node, rebuilding it from the registry on first use.
+    ///
+    /// # Errors
+    /// Returns an Err if the Node does not wrap a Function, or if its Function can not be rebuilt.
+    fn function(&self) -> Result<Arc<dyn Function>, &'static str> {
+        let mut callable = self.callable.lock().unwrap();
+        if callable.is_none() {
+            let (name, configuration) = self.function.as_ref().ok_or("Node does not wrap a function.")?;
+            *callable = Some(resolve_function(name, configuration)?);
+        }
+        Ok(callable.clone().unwrap())
+    }
+
+    /// Applies the Node's Function to its inputs.
+    ///
+    /// The outputs replace the output layer's entries, so they are passed to peers and the parent like the outputs of any other Node.
+    /// Outputs of the previous pass that the Function no longer returns are removed.
+    ///
+    /// # Errors
+    /// Returns an Err if the Function is not registered or fails to transform the inputs.
+    fn apply_function(&self) -> Result<(), &'static str> {
+        let (function, mesh) = (self.function()?, self.mesh());
+        let outputs = function.call(&mesh.layers()[0].lock().unwrap().tensor)?;
+        let mut output_layer = mesh.layers().last().unwrap().lock().unwrap();
+        output_layer.tensor.hash_map.keys().for_each(|key| { mesh.tensor().hash_map.remove(key); });
+        outputs.iter().for_each(|(key, value)| mesh.tensor().insert(key, value.clone()));
+        output_layer.tensor = outputs;
+        Ok(())
+    }
+
+    /// Clears the state carried across passes by recurrent layers, in this Node and all of its children.
+    ///
+    /// # Example(s)
+    /// ```
+    /// use uuid::Uuid;
+    /// use anneml::node::attribute::{Activation, Attribute, CellType, TensorDescriptor};
+    /// use anneml::node::node::{Node, NodeType};
+    /// use anneml::node::scope::Scope;
+    ///
+    /// let descriptor = vec![("_SYSTEM_WEIGHTS", TensorDescriptor::RandN), ("_SYSTEM_BIASES", TensorDescriptor::Const(0.0)),
+    ///                       ("_SYSTEM_GATE_WEIGHTS", TensorDescriptor::RandN), ("_SYSTEM_GATE_BIASES", TensorDescriptor::Const(0.0))];
+    /// let lstm = Attribute::new(Activation::None, CellType::Lstm, descriptor, Scope::new(0,1));
+    /// let node = Node::new(Uuid::new_v4(), NodeType::Leaf(Attribute::default(), vec![(lstm.clone(), 4), (lstm, 2)]));
+    /// node.reset_state();
+    /// ```
+    pub fn reset_state(&self) {
+        self.mesh().layers().iter().for_each(|layer| layer.lock().unwrap().reset_state());
+        self.children.iter().flatten().for_each(|child| child.lock().unwrap().reset_state());
+    }
+
+    /// Trains a leaf Node on a single sample, returning the loss of the pass.
+    ///
+    /// `tensor` passes through the Node in `Mode::Train`, after which the squared error between its output and `target` is
+    /// propagated back through its layers, every parameter descending its gradient by `rate`.
+    /// The loss is half the sum of the squared differences between the output and `target`, before the parameters are updated.
+    ///
+    /// # Errors
+    /// Returns an Err if the Node is not a built leaf, if one of its layers can not be trained, if `target` does not match
+    /// the size of its output, if the pass fails or if a custom Cell does not implement backward.
+    pub(crate) fn train(&self, tensor: &Tensor, target: &Array<f64>, rate: f64) -> Result<f64, &'static str> {
+        if !self.is_leaf_node() { return Err("Only leaf Nodes can be trained.") }
+        if !self.mesh().tensor().hash_map.contains_key("_SYSTEM_VALUES") { return Err("Node must be built before training.") }
+        trainable(&self.mesh(), target)?;
+        self.mesh().layers()[0].lock().unwrap().tensor.merge(tensor, &self.mesh().edges().input_from_parent);
+        let traces = self.process(self.mesh(), Mode::Train)?;
+        backpropagate(&self.mesh(), &traces, target, rate)
     }
 
     /// Grabs the output values from children nodes and join them in a single column.
     pub fn output(&self) -> Array<f64> {
-        join_many(1, self.mesh().tensor().hash_map.iter().filter(|(k, v)| !k.contains("_SYSTEM")).map(|(k, v)| v).collect::<Vec<&Array<f64>>>())
+        join_many(1, self.mesh().tensor().hash_map.iter().filter(|(k, _)| !k.contains("_SYSTEM")).map(|(_, v)| v).collect::<Vec<&Array<f64>>>())
     }
 
     pub(crate) fn mesh(&self) -> Arc<Mesh> {
         self.mesh.clone()
     }
 
-    pub(crate) fn uuid(&self) -> Uuid {
+    /// Acquire the UUID of the Node.
+    pub fn uuid(&self) -> Uuid {
         self.uuid
     }
 
+    /// Acquire the name of the Function wrapped by a function Node.
+    pub(crate) fn function_name(&self) -> Option<&str> {
+        self.function.as_ref().map(|(name, _)| name.as_str())
+    }
+
+    /// Acquire the VertexKind of a vertex Node.
+    pub(crate) fn vertex_kind(&self) -> Option<&VertexKind> {
+        self.kind.as_ref()
+    }
+
+    /// Acquire the configuration of the Function wrapped by a function Node.
+    pub(crate) fn function_configuration(&self) -> Option<&str> {
+        self.function.as_ref().map(|(_, configuration)| configuration.as_str())
+    }
+
+    /// Acquire the name of the Node, if it has one.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Assigns a human-readable name to the Node, used by path lookups.
+    ///
+    /// # Example(s)
+    /// ```
+    /// use uuid::Uuid;
+    /// use anneml::node::attribute::Attribute;
+    /// use anneml::node::node::{Node, NodeType};
+    ///
+    /// let attribute = Attribute::default();
+    /// let node = Node::new(Uuid::new_v4(), NodeType::Leaf(attribute.clone(), vec![(attribute, 2)])).with_name("classifier");
+    /// assert_eq!(Some("classifier"), node.name());
+    /// ```
+    pub fn with_name(mut self, name: &str) -> Node {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// Searches the descendants of the Node for the Node with `uuid`, depth first.
+    ///
+    /// # Example(s)
+    /// ```
+    /// use uuid::Uuid;
+    /// use anneml::node::attribute::Attribute;
+    /// use anneml::node::node::{Node, NodeType, VertexKind};
+    ///
+    /// let attribute = Attribute::default();
+    /// let uuid = Uuid::new_v4();
+    /// let leaf_node = Node::new(uuid, NodeType::Leaf(attribute.clone(), vec![(attribute.clone(), 2)]));
+    /// let vertex_node = Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, attribute.clone(), leaf_node, attribute.clone()));
+    /// assert_eq!(uuid, vertex_node.find_by_uuid(uuid).unwrap().lock().unwrap().uuid());
+    /// assert!(vertex_node.find_by_uuid(Uuid::new_v4()).is_none());
+    /// ```
+    pub fn find_by_uuid(&self, uuid: Uuid) -> Option<Arc<Mutex<Node>>> {
+        self.children.iter().flatten().find_map(|child| {
+            let node = child.lock().unwrap();
+            if node.uuid == uuid { Some(child.clone()) } else { node.find_by_uuid(uuid) }
+        })
+    }
+
+    /// Follows a path of descendants, relative to the Node.
+    ///
+    /// Each segment of the `/` separated path selects the first child, column by column, whose name or UUID matches it.
+    ///
+    /// # Example(s)
+    /// ```
+    /// use uuid::Uuid;
+    /// use anneml::node::attribute::Attribute;
+    /// use anneml::node::node::{Node, NodeType, VertexKind};
+    ///
+    /// let attribute = Attribute::default();
+    /// let classifier = Node::new(Uuid::new_v4(), NodeType::Leaf(attribute.clone(), vec![(attribute.clone(), 2)])).with_name("classifier");
+    /// let encoder = Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, attribute.clone(), classifier, attribute.clone())).with_name("encoder");
+    /// let root = Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, attribute.clone(), encoder, attribute.clone()));
+    /// assert!(root.find_by_path("encoder/classifier").unwrap().lock().unwrap().is_leaf_node());
+    /// assert!(root.find_by_path("encoder/missing").is_none());
+    /// ```
+    pub fn find_by_path(&self, path: &str) -> Option<Arc<Mutex<Node>>> {
+        let (segment, rest) = match path.split_once('/') {
+            Some((segment, rest)) => { (segment, Some(rest)) }
+            None => { (path, None) }
+        };
+        let child = self.children.iter().flatten().find(|child| child.lock().unwrap().matches(segment))?.clone();
+        match rest {
+            Some(rest) => { child.lock().unwrap().find_by_path(rest) }
+            None => { Some(child) }
+        }
+    }
+
+    /// Returns a deep copy of the Node and its descendants, sharing no state with the Node.
+    ///
+    /// With `CloneWeights::Identical` the copy holds the same values as the Node.
+    /// With `CloneWeights::Reinitialized` every Node of the copy that was built is built again, drawing fresh values from its TensorDescriptors.
+    /// The copy is first given a seed derived from the Node's seed and the copy's UUID, so its random values differ from the Node's
+    /// even when it keeps the same UUIDs, while copying the same Node always draws the same values.
+    /// The seed is replaced by the parent's once the copy is added to a Vertex, without changing the values already drawn.
+    ///
+    /// If `regenerate_uuids` is true, every Node of the copy receives a new UUID and the entries keyed by the previous UUIDs are renamed,
+    /// in the Tensors of its layers and Mesh as well as in the key chains of its Edges. The copy's outputs then do not collide with the Node's inside a parent.
+    ///
+    /// # Errors
+    /// Returns an Err if a Node of the copy could not be built again.
+    ///
+    /// # Example(s)
+    /// ```
+    /// use uuid::Uuid;
+    /// use anneml::node::attribute::Attribute;
+    /// use anneml::node::node::{CloneWeights, Node, NodeType, VertexKind};
+    ///
+    /// let attribute = Attribute::default();
+    /// let leaf_node = Node::new(Uuid::new_v4(), NodeType::Leaf(attribute.clone(), vec![(attribute.clone(), 2)]));
+    /// let mut vertex_node = Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, attribute.clone(), leaf_node, attribute.clone()));
+    ///
+    /// let copy = vertex_node.children()[0][0].lock().unwrap().deep_clone(CloneWeights::Identical, true).unwrap();
+    /// assert_ne!(vertex_node.children()[0][0].lock().unwrap().uuid(), copy.uuid());
+    /// vertex_node.add_child_to_parent(copy, None).unwrap();
+    /// ```
+    pub fn deep_clone(&self, weights: CloneWeights, regenerate_uuids: bool) -> Result<Node, &'static str> {
+        let mut copy: Node = bincode::deserialize(&bincode::serialize(self).unwrap()).unwrap();
+        if regenerate_uuids {
+            let uuids = copy.uuids().into_iter().map(|uuid| (uuid, Uuid::new_v4())).collect::<FxHashMap<Uuid, Uuid>>();
+            let names = uuids.iter().map(|(previous, uuid)| (previous.to_string(), uuid.to_string())).collect::<FxHashMap<String, String>>();
+            copy.rename_uuids(&uuids, &names);
+        }
+        if let CloneWeights::Reinitialized = weights {
+            // The last stream of the copy's UUID is kept apart from the streams of its layers.
+            copy.set_seed(stream_seed(self.seed, copy.uuid.as_u128(), u64::MAX));
+            copy.reinitialize()?;
+        }
+        Ok(copy)
+    }
+
+    /// Returns the UUIDs of the Node and its descendants.
+    fn uuids(&self) -> Vec<Uuid> {
+        std::iter::once(self.uuid).chain(self.children.iter().flatten().flat_map(|child| child.lock().unwrap().uuids())).collect()
+    }
+
+    /// Assigns new UUIDs to the Node and its descendants, renaming the entries keyed by the previous UUIDs.
+    fn rename_uuids(&mut self, uuids: &FxHashMap<Uuid, Uuid>, names: &FxHashMap<String, String>) {
+        self.uuid = uuids[&self.uuid];
+        self.mesh.layers().iter().for_each(|layer| layer.lock().unwrap().tensor.rename(names));
+        self.mesh.tensor().rename(names);
+        self.mesh.edges().rename(names);
+        self.children.iter().flatten().for_each(|child| child.lock().unwrap().rename_uuids(uuids, names));
+    }
+
+    /// Builds again the leaves that were built, and drops the aggregation weights of the vertices so they are rebuilt on the next pass.
+    ///
+    /// # Errors
+    /// Returns an Err if a leaf could not be built from its Attributes' TensorDescriptors.
+    fn reinitialize(&self) -> Result<(), &'static str> {
+        if self.is_leaf_node() && self.mesh.tensor().get("_SYSTEM_VALUES").is_some() { self.init_leaf()? }
+        if !self.is_leaf_node() && !self.is_function_node() { self.invalidate_aggregation_weights() }
+        self.children.iter().flatten().try_for_each(|child| child.lock().unwrap().reinitialize())
+    }
+
+    /// Returns whether a path segment refers to the Node, by name or UUID.
+    pub(crate) fn matches(&self, segment: &str) -> bool {
+        self.name.as_deref() == Some(segment) || self.uuid.to_string() == segment
+    }
+
+    /// Assigns the random seed to this Node and all of its children.
+    pub(crate) fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.children.iter().flatten().for_each(|child| child.lock().unwrap().set_seed(seed));
+    }
+
     /// Acquire reference to Node Children Vector.
     pub fn children(&self) -> &Vec<Vec<Arc<Mutex<Node>>>> {
         &self.children
@@ -317,22 +1088,112 @@ impl Node {
 impl Processor for Node {
     /// Recursively traverse through child nodes and instantiate values..
     /// tensors passed into traverse are assigned to the input layer of the Node.
-    fn traverse(&self, tensor: &Tensor, build: bool) {
+    /// The Mode of the pass is handed down to every child.
+    ///
+    /// # Errors
+    /// Returns the first Err raised while building the Node or one of its descendants, the remaining Nodes not being traversed.
+    fn traverse(&self, tensor: &Tensor, build: bool, mode: Mode) -> Result<(), &'static str> {
         self.mesh().layers()[0].lock().unwrap().tensor.merge(tensor, &self.mesh().edges().input_from_parent);
+        if self.is_function_node() { return self.apply_function() }
         match self.is_leaf_node() {
-            true =>  { if build { self.init_leaf() } self.process(self.mesh(), tensor) }
+            true =>  { if build { self.init_leaf()? } self.process(self.mesh(), mode)?; }
             false => {
+                if build { self.invalidate_aggregation_weights() }
+                match &self.kind {
+                    Some(VertexKind::Gated(selection)) => { return self.traverse_gated(build, mode, selection) }
+                    Some(VertexKind::Routed(routing)) => { return self.traverse_routed(build, mode, routing) }
+                    _ => {}
+                }
                 let children = &self.children.iter().flatten().map(|child| child.lock().unwrap());
-                children.clone().for_each(|child| { self.assign_children_inputs(self.mesh().layers(), &child, build); });
+                children.clone().try_for_each(|child| self.traverse_child(&child, build, mode))?;
                 children.clone().for_each(|child| { self.assign_sibling_inputs(&self.children, &child); });
                 children.clone().for_each(|child| { self.assign_parent_outputs(self.mesh().tensor(), &child); });
+                self.aggregate_outputs()?;
             }
         }
+        Ok(())
     }
 }
 
-/// Defines the values associated with Leaf and Vertex Nodes.
+/// Defines the values associated with Leaf, Vertex and Function Nodes.
+///
+/// `Vertex(kind, input, child, output)` creates a vertex traversing its children according to `kind`, starting with a single child.
+/// `Function(name, configuration)` wraps the Function whose constructor is registered under `name`. See `NodeType::function`.
+#[allow(clippy::large_enum_variant)]
 pub enum NodeType {
     Leaf(Attribute, Vec<(Attribute, u64)>),
-    Vertex(Attribute, Node, Attribute),
+    Vertex(VertexKind, Attribute, Node, Attribute),
+    Function(String, String),
+}
+
+/// Defines how a vertex traverses its children.
+///
+/// `Aggregate` traverses every child, their outputs being combined by the Aggregation of the vertex's output Attribute.
+/// `Gated(selection)` turns the vertex into a mixture of experts: its first child is a gate whose outputs, one per remaining child,
+/// are turned into weights by a softmax. Only the experts picked by `selection` are traversed and combined in a mean weighted by the gate,
+/// except while building, when every expert is traversed so it gets built. The combined output is keyed by the vertex's UUID.
+/// `Routed(routing)` turns the vertex into a router: the first Node of its first child column outputs one score per remaining column (route),
+/// and only the routes picked by `routing` are traversed, except while building.
+/// The router may be a classifier or a function Node applying a rule. The outputs of the routes taken are kept under their own keys,
+/// and `Node::route` reports which routes were taken.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Default, PartialEq, Debug)]
+pub enum VertexKind {
+    #[default]
+    Aggregate,
+    Gated(Selection),
+    Routed(Routing),
+}
+
+/// Experts traversed by a `VertexKind::Gated` vertex.
+///
+/// `All` traverses every expert, `TopK(k)` only the `k` experts the gate weighs the most.
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, Debug)]
+pub enum Selection {
+    All,
+    TopK(usize),
+}
+
+/// Routes traversed by a `VertexKind::Routed` vertex.
+///
+/// `Best` traverses the highest scoring route, `Threshold(t)` every route scoring at least `t`.
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, Debug)]
+pub enum Routing {
+    Best,
+    Threshold(f64),
+}
+
+/// Determines the values held by a deep copy of a Node. See `Node::deep_clone`.
+pub enum CloneWeights {
+    Identical,
+    Reinitialized,
+}
+
+impl NodeType {
+    /// Creates the NodeType of a function Node, recording the Function's name and configuration.
+    ///
+    /// The Function's constructor has to be registered under its name before the Node is traversed.
+    ///
+    /// # Example(s)
+    /// ```
+    /// use uuid::Uuid;
+    /// use anneml::node::function::Function;
+    /// use anneml::node::node::{Node, NodeType};
+    /// use anneml::node::tensor::Tensor;
+    ///
+    /// struct Passthrough;
+    ///
+    /// impl Function for Passthrough {
+    ///     fn name(&self) -> &str { "passthrough" }
+    ///     fn call(&self, inputs: &Tensor) -> Result<Tensor, &'static str> {
+    ///         Ok(Tensor::new(&inputs.iter().map(|(key, value)| (key.as_str(), value.clone())).collect::<Vec<_>>()))
+    ///     }
+    /// }
+    ///
+    /// let node = Node::new(Uuid::new_v4(), NodeType::function(&Passthrough));
+    /// assert!(node.is_function_node());
+    /// assert!(!node.is_leaf_node());
+    /// ```
+    pub fn function(function: &dyn Function) -> NodeType {
+        NodeType::Function(function.name().to_string(), function.configuration())
+    }
 }
\ No newline at end of file
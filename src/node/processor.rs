@@ -5,4 +5,15 @@
  * (c) Copyright by Christian Potts
  */
 
-pub(crate) mod processor;
\ No newline at end of file
+#[allow(clippy::module_inception)]
+pub(crate) mod processor;
+pub(crate) mod recurrent;
+pub(crate) mod convolution;
+pub(crate) mod pooling;
+pub(crate) mod attention;
+pub(crate) mod normalization;
+
+pub(crate) mod embedding;
+pub(crate) mod radial;
+pub(crate) mod training;
+pub(crate) mod aggregation;
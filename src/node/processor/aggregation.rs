@@ -0,0 +1,39 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use arrayfire::{Array, dim4, eq, imax, imax_all, join_many, max, mean, mul, range, sum, sum_all};
+use crate::node::attribute::Aggregation;
+
+/// Combines the outputs of children element-wise, one output per row.
+///
+/// `weights` holds one weight per child, and is only read by `Aggregation::WeightedMean`.
+///
+/// # Errors
+/// Returns an Err if there is no output, if the outputs differ in size, or if weights are missing or do not match the outputs.
+pub(crate) fn aggregate(outputs: &[Array<f64>], aggregation: &Aggregation, weights: Option<&Array<f64>>) -> Result<Array<f64>, &'static str> {
+    let width = outputs.first().ok_or("Vertex has no child output to aggregate.")?.elements() as u64;
+    if outputs.iter().any(|output| output.elements() as u64 != width) { return Err("Aggregated child outputs differ in size.") }
+    let stacked = join_many(0, outputs.iter().collect::<Vec<&Array<f64>>>());
+    let children = outputs.len() as u64;
+    match aggregation {
+        Aggregation::Concatenate => { Ok(join_many(1, outputs.iter().collect::<Vec<&Array<f64>>>())) }
+        Aggregation::Sum => { Ok(sum(&stacked, 0)) }
+        Aggregation::Mean => { Ok(mean(&stacked, 0)) }
+        Aggregation::Max => { Ok(max(&stacked, 0)) }
+        Aggregation::WeightedMean => {
+            let weights = weights.ok_or("Weighted mean aggregation requires _SYSTEM_AGGREGATION_WEIGHTS.")?;
+            if weights.elements() as u64 != children { return Err("Aggregation weights do not match the number of children.") }
+            Ok(sum(&mul(&stacked, weights, true), 0) / sum_all(weights).0)
+        }
+        Aggregation::Vote => {
+            let (_, classes) = imax(&stacked, 1);
+            let ballots = eq(&range::<u32>(dim4!(children, width, 1, 1), 1), &classes, true);
+            let (_, _, majority) = imax_all(&sum(&ballots.cast::<f64>(), 0));
+            Ok(eq(&range::<f64>(dim4!(1, width, 1, 1), 1), &(majority as f64), false).cast::<f64>())
+        }
+    }
+}
@@ -0,0 +1,38 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use arrayfire::{add, Array, dim4, div, exp, index, MatProp, matmul, max, moddims, mul, seq, sub, sum, transpose};
+use crate::node::tensor::Tensor;
+
+/// Applies the projection `projection` (0 = query, 1 = key, 2 = value, 3 = output) to the tokens, one token per row.
+fn project(tensor: &Tensor, tokens: &Array<f64>, projection: i32) -> Array<f64> {
+    let weights = index(tensor.hash_map.get("_SYSTEM_ATTENTION_WEIGHTS").unwrap(), &[seq!(), seq!(), seq!(projection, projection, 1)]);
+    let biases = index(tensor.hash_map.get("_SYSTEM_ATTENTION_BIASES").unwrap(), &[seq!(), seq!(), seq!(projection, projection, 1)]);
+    add(&matmul(tokens, &weights, MatProp::NONE, MatProp::NONE), &biases, true)
+}
+
+/// Softmax along the rows of every batch.
+pub(crate) fn softmax(array: &Array<f64>) -> Array<f64> {
+    let exp = exp(&sub(array, &max(array, 1), true));
+    div(&exp, &sum(&exp, 1), true)
+}
+
+/// Multi-head scaled dot-product self-attention over the output of the previous layer.
+///
+/// The previous layer is read as a sequence of tokens of size `dimension`, one token after the other.
+/// Every head attends over the whole sequence with its own slice of the query, key and value projections.
+/// The joined heads pass through the output projection, keeping the sequence layout of the input.
+pub(crate) fn attend(tensor: &Tensor, dimension: usize, heads: usize, input: Array<f64>) -> Array<f64> {
+    let (dimension, heads) = (dimension as u64, heads as u64);
+    let length = input.elements() as u64 / dimension;
+    let tokens = transpose(&moddims(&input, dim4!(dimension, length, 1, 1)), false);
+    let split = |array: Array<f64>| moddims(&array, dim4!(length, dimension / heads, heads, 1));
+    let (query, key, value) = (split(project(tensor, &tokens, 0)), split(project(tensor, &tokens, 1)), split(project(tensor, &tokens, 2)));
+    let scores = mul(&matmul(&query, &key, MatProp::NONE, MatProp::TRANS), &(1.0 / ((dimension / heads) as f64).sqrt()), false);
+    let attended = moddims(&matmul(&softmax(&scores), &value, MatProp::NONE, MatProp::NONE), dim4!(length, dimension, 1, 1));
+    moddims(&transpose(&project(tensor, &attended, 3), false), dim4!(1, length * dimension, 1, 1))
+}
@@ -0,0 +1,26 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use arrayfire::{add, Array, dim4, MatProp, matmul, moddims, reorder_v2, unwrap};
+use crate::node::kernel::Kernel;
+use crate::node::tensor::Tensor;
+
+/// Convolves the output of the previous layer with the layer's kernels.
+///
+/// Input planes are unwrapped into patches, which are multiplied with the kernels of every output channel.
+/// The result holds the output channels one after the other, each plane being stored in column-major order.
+pub(crate) fn convolve(tensor: &Tensor, kernel: &Kernel, input: Array<f64>) -> Array<f64> {
+    let (rows, cols) = kernel.input_shape(input.elements()).unwrap();
+    let (size, stride, padding, channels) = (kernel.size(), kernel.stride(), kernel.padding(), kernel.channels());
+    let planes = moddims(&input, dim4!(rows as u64, cols as u64, channels.0 as u64, 1));
+    let patches = unwrap(&planes, size.0 as i64, size.1 as i64, stride.0 as i64, stride.1 as i64, padding.0 as i64, padding.1 as i64, true);
+    let (patch, positions) = ((size.0 * size.1 * channels.0) as u64, patches.dims()[1]);
+    let patches = moddims(&reorder_v2(&patches, 0, 2, Some(vec![1])), dim4!(patch, positions, 1, 1));
+    let kernels = moddims(tensor.hash_map.get("_SYSTEM_KERNELS").unwrap(), dim4!(patch, channels.1 as u64, 1, 1));
+    let output = add(&matmul(&patches, &kernels, MatProp::TRANS, MatProp::NONE), tensor.hash_map.get("_SYSTEM_KERNEL_BIASES").unwrap(), true);
+    moddims(&output, dim4!(1, positions * channels.1 as u64, 1, 1))
+}
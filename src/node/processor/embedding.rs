@@ -0,0 +1,23 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use arrayfire::{Array, dim4, flat, lookup, max_all, min_all, moddims, transpose};
+use crate::node::tensor::Tensor;
+
+/// Looks up one row of `_SYSTEM_EMBEDDINGS` for every index of the input.
+///
+/// Indices are truncated to integers. The rows are placed one after the other in a single row.
+///
+/// # Errors
+/// Returns an Err if an index is outside of the vocabulary.
+pub(crate) fn embed(tensor: &Tensor, input: Array<f64>) -> Result<Array<f64>, &'static str> {
+    let embeddings = tensor.hash_map.get("_SYSTEM_EMBEDDINGS").unwrap();
+    let (vocabulary, dimension) = (embeddings.dims()[0], embeddings.dims()[1]);
+    if min_all(&input).0 < 0.0 || max_all(&input).0 >= vocabulary as f64 { return Err("Embedding index is outside of the vocabulary.") }
+    let rows = lookup(embeddings, &flat(&input).cast::<u32>(), 0);
+    Ok(moddims(&transpose(&rows, false), dim4!(1, input.elements() as u64 * dimension, 1, 1)))
+}
@@ -0,0 +1,34 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use arrayfire::{add, Array, div, mean, mul, sqrt, sub, var_v2, VarianceBias};
+use crate::node::attribute::Normalization;
+use crate::node::mode::Mode;
+use crate::node::tensor::Tensor;
+
+/// Small constant keeping the normalization away from a division by zero.
+const EPSILON: f64 = 1e-5;
+
+/// Normalizes the layer values, then applies the layer's scale and shift.
+pub(crate) fn normalize(tensor: &mut Tensor, normalization: &Normalization, array: Array<f64>, mode: Mode) -> Array<f64> {
+    let (mean, variance) = match normalization {
+        Normalization::None => { return array }
+        Normalization::Batch(momentum) => {
+            if mode == Mode::Train {
+                let running_mean = add(&mul(tensor.hash_map.get("_SYSTEM_RUNNING_MEAN").unwrap(), &(1.0 - momentum), false), &mul(&array, momentum, false), false);
+                let deviation = sub(&array, &running_mean, false);
+                let running_variance = add(&mul(tensor.hash_map.get("_SYSTEM_RUNNING_VARIANCE").unwrap(), &(1.0 - momentum), false), &mul(&mul(&deviation, &deviation, false), momentum, false), false);
+                tensor.insert("_SYSTEM_RUNNING_MEAN", running_mean);
+                tensor.insert("_SYSTEM_RUNNING_VARIANCE", running_variance);
+            }
+            (tensor.hash_map.get("_SYSTEM_RUNNING_MEAN").unwrap().clone(), tensor.hash_map.get("_SYSTEM_RUNNING_VARIANCE").unwrap().clone())
+        }
+        Normalization::Layer => { (mean(&array, 1), var_v2(&array, VarianceBias::POPULATION, 1)) }
+    };
+    let normalized = div(&sub(&array, &mean, true), &sqrt(&add(&variance, &EPSILON, false)), true);
+    add(&mul(&normalized, tensor.hash_map.get("_SYSTEM_NORM_SCALE").unwrap(), false), tensor.hash_map.get("_SYSTEM_NORM_SHIFT").unwrap(), false)
+}
@@ -0,0 +1,55 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use arrayfire::{Array, assign_seq, constant, dim4, max, mean, moddims, seq, sum, unwrap};
+use crate::node::kernel::Kernel;
+
+/// Flag that selects how a pooling window is reduced.
+pub(crate) enum Reduce {
+    Max,
+    Average,
+}
+
+fn reduce(array: &Array<f64>, reduce: &Reduce) -> Array<f64> {
+    match reduce {
+        Reduce::Max => { max(array, 0) }
+        Reduce::Average => { mean(array, 0) }
+    }
+}
+
+/// Pools every channel of the previous layer's output over the Kernel's windows.
+///
+/// Padding never wins a maximum and is left out of averages, so each window only reduces the values it covers.
+/// The result holds the channels one after the other, each plane being stored in column-major order.
+pub(crate) fn pool(kernel: &Kernel, input: Array<f64>, reduction: &Reduce) -> Array<f64> {
+    let (rows, cols) = kernel.input_shape(input.elements()).unwrap();
+    let (size, stride, padding, channels) = (kernel.size(), kernel.stride(), kernel.padding(), kernel.channels().0 as u64);
+    let planes = moddims(&input, dim4!(rows as u64, cols as u64, channels, 1));
+    let windows = |planes: &Array<f64>, fill: f64| unwrap(&pad(planes, padding, fill), size.0 as i64, size.1 as i64, stride.0 as i64, stride.1 as i64, 0, 0, true);
+    let pooled = match reduction {
+        Reduce::Max => { max(&windows(&planes, f64::NEG_INFINITY), 0) }
+        Reduce::Average => { sum(&windows(&planes, 0.0), 0) / sum(&windows(&constant(1.0, planes.dims()), 0.0), 0) }
+    };
+    let positions = pooled.dims()[1];
+    moddims(&pooled, dim4!(1, positions * channels, 1, 1))
+}
+
+/// Surrounds every plane with (rows, columns) of `padding` filled with `fill`.
+fn pad(planes: &Array<f64>, padding: (usize, usize), fill: f64) -> Array<f64> {
+    if padding == (0, 0) { return planes.clone() }
+    let dims = planes.dims();
+    let mut padded = constant(fill, dim4!(dims[0] + 2 * padding.0 as u64, dims[1] + 2 * padding.1 as u64, dims[2], 1));
+    let seqs = [seq!(padding.0 as i32, (padding.0 as u64 + dims[0] - 1) as i32, 1), seq!(padding.1 as i32, (padding.1 as u64 + dims[1] - 1) as i32, 1), seq!(0, (dims[2] - 1) as i32, 1)];
+    assign_seq(&mut padded, &seqs, planes);
+    padded
+}
+
+/// Pools every channel of the previous layer's output into a single value.
+pub(crate) fn global_pool(channels: usize, input: Array<f64>, reduction: &Reduce) -> Array<f64> {
+    let planes = moddims(&input, dim4!(input.elements() as u64 / channels as u64, channels as u64, 1, 1));
+    moddims(&reduce(&planes, reduction), dim4!(1, channels as u64, 1, 1))
+}
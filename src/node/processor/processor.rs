@@ -6,65 +6,95 @@
  */
 
 use std::sync::{Arc, Mutex, MutexGuard};
-use arrayfire::{add, Array, assign_seq, index, join_many, mul, seq, sigmoid, sum, tanh, transpose};
+use arrayfire::{add, Array, assign_seq, constant, ge, index, random_uniform, join_many, mul, seq, sigmoid, sum, tanh, transpose};
 use crate::node::attribute::{Activation, CellType};
-use crate::node::edges::NodeRange;
 use crate::node::layer::Layer;
 use crate::node::mesh::Mesh;
+use crate::node::mode::Mode;
 use crate::node::node::Node;
+use crate::node::processor::attention::attend;
+use crate::node::processor::convolution::convolve;
+use crate::node::processor::embedding::embed;
+use crate::node::processor::normalization::normalize;
+use crate::node::processor::pooling::{global_pool, pool, Reduce};
+use crate::node::processor::radial::{rbf, som};
+use crate::node::processor::recurrent::{gru, lstm};
 use crate::node::tensor::Tensor;
 
+/// Values a layer produced during a pass, kept to propagate gradients back through it.
+pub(crate) struct Trace {
+    /// Values the layer's cell received.
+    pub(crate) source: Array<f64>,
+    /// Values after the layer's activation, before dropout.
+    pub(crate) activated: Array<f64>,
+    /// Values the layer passed on.
+    pub(crate) output: Array<f64>,
+    /// Scaled dropout mask applied to the activated values, if any.
+    pub(crate) mask: Option<Array<f64>>,
+}
+
 pub(crate) trait Processor {
 
-    fn traverse(&self, tensor: &Tensor, build: bool);
+    fn traverse(&self, tensor: &Tensor, build: bool, mode: Mode) -> Result<(), &'static str>;
 
-    fn assign_children_inputs(&self, mut layers: &Vec<Mutex<Layer>>, child: &MutexGuard<Node>, build: bool) {
-        child.traverse(&layers[0].lock().unwrap().tensor, build);
+    fn assign_children_inputs(&self, layers: &[Mutex<Layer>], child: &MutexGuard<Node>, build: bool, mode: Mode) -> Result<(), &'static str> {
+        child.traverse(&layers[0].lock().unwrap().tensor, build, mode)?;
         layers[1].lock().unwrap().tensor.merge(&child.mesh().layers().last().unwrap().lock().unwrap().tensor, &child.mesh().edges().output_to_parent);
+        Ok(())
     }
 
-    fn assign_sibling_inputs(&self, children: &Vec<Vec<Arc<Mutex<Node>>>>, child: &MutexGuard<Node>) {
+    fn assign_sibling_inputs(&self, children: &[Vec<Arc<Mutex<Node>>>], child: &MutexGuard<Node>) {
         child.mesh().edges().input_from_peer_output.iter().for_each(|link|
             child.mesh().layers()[0].lock().unwrap().tensor.merge(&children[link.0.0][link.0.1].lock().unwrap().mesh().layers().last().unwrap().lock().unwrap().tensor, &link.1));
     }
 
     fn assign_parent_outputs(&self, mut tensor: MutexGuard<Tensor>, child: &MutexGuard<Node>) {
-        child.mesh().tensor().hash_map.iter().filter(|(key,_)|
-            match &child.mesh().edges().output_to_parent {
-                NodeRange::Selective(key_chain) => { key_chain.contains(key) }
-                _ => { true }
-            }).for_each(|(key, value)| { if !key.contains("_SYSTEM") { tensor.insert(key, value.clone()); } }  );
+        child.mesh().edges().output_to_parent.select(&child.mesh().tensor()).into_iter()
+            .for_each(|(key, value)| { if !key.contains("_SYSTEM") { tensor.insert(&key, value); } }  );
     }
 
-    fn process(&self, mesh: Arc<Mesh>, tensor: &Tensor) {
-
-        // Filter the inputs by what is allowed in this layer.
-        let filtered_inputs = join_many(1,
-                                            tensor.hash_map.iter().filter(|(key, _)| {
+    /// Passes the inputs through the layers of a leaf, returning the Trace of each layer.
+    ///
+    /// The inputs are the entries of the input layer: those the edges let in from the parent, and the outputs of the Node's peers.
+    ///
+    /// # Errors
+    /// Returns an Err if a cell reads an input entry the input layer did not receive.
+    fn process(&self, mesh: Arc<Mesh>, mode: Mode) -> Result<Vec<Trace>, &'static str> {
 
-                                                if key.contains("_SYSTEM") { return false }
-                                                match &mesh.edges().input_from_parent {
-                                                    NodeRange::Selective(key_chain) => { key_chain.contains(key) }
-                                                    NodeRange::All => { true }
-                                                }
-                                            }).map(|(_, value)| value ).collect::<Vec<&Array<f64>>>()
-        ); // Get the remaining Arrays.
+        // Join the entries of the input layer.
+        let filtered_inputs = {
+            let input = mesh.layers()[0].lock().unwrap();
+            join_many(1, input.tensor.hash_map.iter().filter(|(key, _)| !key.contains("_SYSTEM")).map(|(_, value)| value).collect::<Vec<&Array<f64>>>())
+        };
 
-        // Pass data in to update values inputs.
+        // Clear values of any previous pass, then pass data in to update values inputs.
+        let dims = mesh.tensor().hash_map.get("_SYSTEM_VALUES").unwrap().dims();
+        mesh.tensor().insert("_SYSTEM_VALUES", constant(0.0, dims));
 
         assign_seq(mesh.tensor().hash_map.get_mut("_SYSTEM_VALUES").unwrap(), &[seq!(0,0,1), seq!(0,(filtered_inputs.dims()[1] - 1) as i32, 1), seq!(0, 0, 1)], &filtered_inputs);
 
         let topology = mesh.topology();
+        let mut traces = Vec::with_capacity(topology.len());
 
         for (i, layer) in mesh.layers().iter().enumerate() {
             let value_seq = [seq!(0,0,1), seq!(0,(topology[i] - 1) as i32, 1), seq!(i as i32, i as i32, 1)];
-            let node_type_process: Array<f64> = node_type(layer.lock().unwrap().attribute.cell_type(), index(mesh.tensor().hash_map.get("_SYSTEM_VALUES").unwrap(), &value_seq));
-            let activation_process = activation(layer.lock().unwrap().attribute.activation(), node_type_process);
+            // Dense cells transform their own values, cells with an input key that input entry, all others the output of the previous layer.
+            let source = if layer.lock().unwrap().is_dense() { i } else { i.saturating_sub(1) };
+            let source_seq = [seq!(0,0,1), seq!(0,(topology[source] - 1) as i32, 1), seq!(source as i32, source as i32, 1)];
+            let input_key = layer.lock().unwrap().attribute.cell_type().input_key().map(str::to_string);
+            let source_values = match input_key {
+                Some(key) => { mesh.layers()[0].lock().unwrap().tensor.hash_map.get(&key).ok_or("Embedding key is not an input of the Node.")?.clone() }
+                None => { index(mesh.tensor().hash_map.get("_SYSTEM_VALUES").unwrap(), &source_seq) }
+            };
+            let node_type_process: Array<f64> = node_type(&mut layer.lock().unwrap(), source_values.clone(), mode)?;
+            let normalization_process = normalization(&mut layer.lock().unwrap(), node_type_process, mode);
+            let activated = activation(layer.lock().unwrap().attribute.activation(), normalization_process);
+            let (activation_process, mask) = dropout(&mut layer.lock().unwrap(), activated.clone(), mode);
             //Update Values
             assign_seq(mesh.tensor().hash_map.get_mut("_SYSTEM_VALUES").unwrap(), &value_seq, &activation_process);
 
             let data = layer.lock().unwrap().domain.domain_data();
-            if i < mesh.layers().len() - 1 + data.0 {
+            if i < mesh.layers().len() - 1 + data.0 && layer.lock().unwrap().tensor.hash_map.contains_key("_SYSTEM_WEIGHTS") {
                 let seq = [seq!(0,0,1),seq!(0, (layer.lock().unwrap().tensor.hash_map.get("_SYSTEM_BIASES").unwrap().dims()[0] - 1) as i32,1),seq!(data.1 as i32,data.3 as i32,1)];
                 let mul = mul(layer.lock().unwrap().tensor.hash_map.get("_SYSTEM_WEIGHTS").unwrap(), &activation_process, true);
                 let sum = sum(&mul, 1);
@@ -73,15 +103,20 @@ pub(crate) trait Processor {
                 let output = add(&index(mesh.tensor().hash_map.get("_SYSTEM_VALUES").unwrap(), &seq), &transpose,false);
                 assign_seq(mesh.tensor().hash_map.get_mut("_SYSTEM_VALUES").unwrap(), &seq, &output);
             }
+            traces.push(Trace { source: source_values, activated, output: activation_process, mask });
         }
 
         //Assign the output as a tensor entry, the key being the guid.
-        // let d2 = (mesh.tensor().hash_map.get("_SYSTEM_VALUES").unwrap().dims()[2] - 1) as i32;
-        // let seq = &[seq!(0,0,1), seq!(0,(mesh.topology().last().unwrap() - 1) as i32, 1), seq!(d2,d2,1)];
-        // let indexed = index(mesh.tensor().hash_map.get("_SYSTEM_VALUES").unwrap(), seq);
-        // mesh.tensor().hash_map.insert("_SYSTEM_VALUES".to_string(), indexed);
-        // arrayfire::print(&mesh.tensor().hash_map.get("_SYSTEM_VALUES").unwrap());
+        let last = topology.len() - 1;
+        if last > 0 {
+            let seq = [seq!(0,0,1), seq!(0,(topology[last] - 1) as i32, 1), seq!(last as i32, last as i32, 1)];
+            let output = index(mesh.tensor().hash_map.get("_SYSTEM_VALUES").unwrap(), &seq);
+            let mut layer = mesh.layers()[last].lock().unwrap();
+            let keys = layer.tensor.hash_map.keys().filter(|key| !key.contains("_SYSTEM")).cloned().collect::<Vec<String>>();
+            keys.iter().for_each(|key| { layer.tensor.insert(key, output.clone()); mesh.tensor().insert(key, output.clone()); });
+        }
 
+        Ok(traces)
     }
 }
 
@@ -93,8 +128,35 @@ fn activation(activation: &Activation, array: Array<f64>) -> Array<f64>{
     }
 }
 
-fn node_type(node_type: &CellType, array: Array<f64>) -> Array<f64>{
-    match &node_type{
+/// Drops values with the layer's dropout rate while training, scaling the remaining values.
+///
+/// Returns the values along with the scaled mask applied to them, if any.
+fn dropout(layer: &mut Layer, array: Array<f64>, mode: Mode) -> (Array<f64>, Option<Array<f64>>){
+    let rate = layer.attribute.dropout();
+    if mode != Mode::Train || rate <= 0.0 { return (array, None) }
+    let mask = ge(&random_uniform::<f64>(array.dims(), &layer.next_pass()), &rate, false).cast::<f64>() / (1.0 - rate);
+    (mul(&array, &mask, false), Some(mask))
+}
+
+fn normalization(layer: &mut Layer, array: Array<f64>, mode: Mode) -> Array<f64>{
+    normalize(&mut layer.tensor, layer.attribute.normalization(), array, mode)
+}
+
+fn node_type(layer: &mut Layer, array: Array<f64>, mode: Mode) -> Result<Array<f64>, &'static str>{
+    Ok(match layer.attribute.cell_type() {
         CellType::Mlp => { array }
-    }
+        CellType::Lstm => { lstm(&mut layer.tensor, array) }
+        CellType::Gru => { gru(&mut layer.tensor, array) }
+        CellType::Conv1d(kernel) | CellType::Conv2d(kernel) => { convolve(&layer.tensor, kernel, array) }
+        CellType::MaxPool(kernel) => { pool(kernel, array, &Reduce::Max) }
+        CellType::AvgPool(kernel) => { pool(kernel, array, &Reduce::Average) }
+        CellType::GlobalMaxPool(channels) => { global_pool(*channels, array, &Reduce::Max) }
+        CellType::GlobalAvgPool(channels) => { global_pool(*channels, array, &Reduce::Average) }
+        CellType::Flatten => { array }
+        CellType::Attention(dimension, heads) => { attend(&layer.tensor, *dimension, *heads, array) }
+        CellType::Embedding(..) => { embed(&layer.tensor, array)? }
+        CellType::Rbf(_) => { rbf(&layer.tensor, array) }
+        CellType::Som(rows, columns, rate, radius) => { som(&mut layer.tensor, *rows, *columns, *rate, *radius, array, mode) }
+        CellType::Custom(..) => { layer.cell()?.forward(&mut layer.tensor, array, mode) }
+    })
 }
\ No newline at end of file
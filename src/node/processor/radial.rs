@@ -0,0 +1,63 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use arrayfire::{Array, dim4, div, eq, exp, flat, imin_all, mul, range, sub, sum, transpose};
+use crate::node::mode::Mode;
+use crate::node::tensor::Tensor;
+
+/// Squared euclidean distance between the input and every row of `prototypes`, as a column.
+fn distances(prototypes: &Array<f64>, input: &Array<f64>) -> Array<f64> {
+    let difference = sub(prototypes, input, true);
+    sum(&mul(&difference, &difference, false), 1)
+}
+
+/// Gaussian radial basis functions over the output of the previous layer.
+///
+/// Every center of `_SYSTEM_RBF_CENTERS` yields exp(-||x - c||² / (2 w²)), w being its width in `_SYSTEM_RBF_WIDTHS`.
+pub(crate) fn rbf(tensor: &Tensor, input: Array<f64>) -> Array<f64> {
+    let centers = tensor.hash_map.get("_SYSTEM_RBF_CENTERS").unwrap();
+    let widths = tensor.hash_map.get("_SYSTEM_RBF_WIDTHS").unwrap();
+    let scale = mul(&mul(widths, widths, false), &2.0, false);
+    transpose(&exp(&(div(&distances(centers, &input), &scale, false) * -1.0)), false)
+}
+
+/// Gradients of the radial basis functions over `input`, given the `gradient` of their outputs.
+///
+/// Returns the gradients of the input, of `_SYSTEM_RBF_CENTERS` and of `_SYSTEM_RBF_WIDTHS`, in that order.
+pub(crate) fn rbf_gradient(tensor: &Tensor, input: &Array<f64>, gradient: &Array<f64>) -> (Array<f64>, Array<f64>, Array<f64>) {
+    let centers = tensor.hash_map.get("_SYSTEM_RBF_CENTERS").unwrap();
+    let widths = tensor.hash_map.get("_SYSTEM_RBF_WIDTHS").unwrap();
+    let squared = mul(widths, widths, false);
+    // Gradient of each function's exponent, as a column.
+    let scaled = transpose(&mul(&rbf(tensor, input.clone()), gradient, false), false);
+    let difference = sub(input, centers, true);
+    let center_gradient = mul(&difference, &div(&scaled, &squared, false), true);
+    let input_gradient = sum(&center_gradient, 0) * -1.0;
+    let width_gradient = div(&mul(&scaled, &distances(centers, input), false), &mul(&squared, widths, false), false);
+    (input_gradient, center_gradient, width_gradient)
+}
+
+/// Kohonen self-organizing map over the output of the previous layer.
+///
+/// Units are laid out column-major on a grid of `rows` by `columns`, each holding a row of `_SYSTEM_SOM_WEIGHTS`.
+/// The unit closest to the input wins the competition and is the only active output.
+/// In `Mode::Train`, every unit moves towards the input by `rate`, weighted by a gaussian neighbourhood of `radius` around the winner on the grid.
+pub(crate) fn som(tensor: &mut Tensor, rows: usize, columns: usize, rate: f64, radius: f64, input: Array<f64>, mode: Mode) -> Array<f64> {
+    let weights = tensor.hash_map.get("_SYSTEM_SOM_WEIGHTS").unwrap().clone();
+    let (_, _, winner) = imin_all(&distances(&weights, &input));
+    let units = range::<f64>(dim4!((rows * columns) as u64, 1, 1, 1), 0);
+    if mode == Mode::Train {
+        let (row, column) = ((winner as usize % rows) as f64, (winner as usize / rows) as f64);
+        let row_distance = flat(&range::<f64>(dim4!(rows as u64, columns as u64, 1, 1), 0)) - row;
+        let column_distance = flat(&range::<f64>(dim4!(rows as u64, columns as u64, 1, 1), 1)) - column;
+        let grid = mul(&row_distance, &row_distance, false) + mul(&column_distance, &column_distance, false);
+        let neighbourhood = exp(&(grid / (-2.0 * radius * radius))) * rate;
+        let update = mul(&neighbourhood, &sub(&input, &weights, true), true);
+        tensor.insert("_SYSTEM_SOM_WEIGHTS", weights + update);
+    }
+    transpose(&eq(&units, &(winner as f64), false).cast::<f64>(), false)
+}
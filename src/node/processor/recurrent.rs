@@ -0,0 +1,51 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use arrayfire::{add, Array, index, join, mul, seq, sigmoid, sub, sum, tanh, transpose};
+use crate::node::tensor::Tensor;
+
+/// Pre-activations of the gates `first..=last` for the joined input `z`.
+///
+/// Gate weights are of size (n, 2n, gates) and gate biases (n, 1, gates), the result being (n, 1, last - first + 1).
+fn gates(tensor: &Tensor, z: &Array<f64>, first: i32, last: i32) -> Array<f64> {
+    let seq = [seq!(), seq!(), seq!(first, last, 1)];
+    let mul = mul(&index(tensor.hash_map.get("_SYSTEM_GATE_WEIGHTS").unwrap(), &seq), z, true);
+    add(&sum(&mul, 1), &index(tensor.hash_map.get("_SYSTEM_GATE_BIASES").unwrap(), &seq), false)
+}
+
+/// Extracts a single gate as a row vector.
+fn gate(gates: &Array<f64>, gate: i32) -> Array<f64> {
+    transpose(&index(gates, &[seq!(), seq!(), seq!(gate, gate, 1)]), false)
+}
+
+/// Long Short Term Memory step.
+///
+/// Gates are ordered input, forget, output and candidate. Hidden and cell state are read from and written back to the layer Tensor.
+pub(crate) fn lstm(tensor: &mut Tensor, input: Array<f64>) -> Array<f64> {
+    let hidden = tensor.hash_map.get("_SYSTEM_HIDDEN").unwrap().clone();
+    let cell = tensor.hash_map.get("_SYSTEM_CELL").unwrap().clone();
+    let gates = gates(tensor, &join(1, &input, &hidden), 0, 3);
+    let (input_gate, forget_gate, output_gate) = (sigmoid(&gate(&gates, 0)), sigmoid(&gate(&gates, 1)), sigmoid(&gate(&gates, 2)));
+    let cell = add(&mul(&forget_gate, &cell, false), &mul(&input_gate, &tanh(&gate(&gates, 3)), false), false);
+    let hidden = mul(&output_gate, &tanh(&cell), false);
+    tensor.insert("_SYSTEM_CELL", cell);
+    tensor.insert("_SYSTEM_HIDDEN", hidden.clone());
+    hidden
+}
+
+/// Gated Recurrent Unit step.
+///
+/// Gates are ordered update, reset and candidate. The candidate sees the hidden state scaled by the reset gate.
+pub(crate) fn gru(tensor: &mut Tensor, input: Array<f64>) -> Array<f64> {
+    let hidden = tensor.hash_map.get("_SYSTEM_HIDDEN").unwrap().clone();
+    let gates = gates(tensor, &join(1, &input, &hidden), 0, 1);
+    let (update_gate, reset_gate) = (sigmoid(&gate(&gates, 0)), sigmoid(&gate(&gates, 1)));
+    let candidate = tanh(&gate(&self::gates(tensor, &join(1, &input, &mul(&reset_gate, &hidden, false)), 2, 2), 0));
+    let hidden = add(&candidate, &mul(&update_gate, &sub(&hidden, &candidate, false), false), false);
+    tensor.insert("_SYSTEM_HIDDEN", hidden.clone());
+    hidden
+}
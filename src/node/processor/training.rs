@@ -0,0 +1,107 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use arrayfire::{add, Array, assign_seq, constant, dim4, index, MatProp, matmul, moddims, mul, seq, sub, sum_all, transpose};
+use crate::node::attribute::{Activation, CellType, Normalization};
+use crate::node::mesh::Mesh;
+use crate::node::processor::processor::Trace;
+use crate::node::processor::radial::rbf_gradient;
+
+/// Checks that every layer of a leaf can be trained, and that `target` matches its output.
+///
+/// Mlp, Rbf, Som and custom cells without normalization can be trained, custom cells through `Cell::backward`.
+///
+/// # Errors
+/// Returns an Err if a layer can not be trained, or if the target does not match the size of the last layer.
+pub(crate) fn trainable(mesh: &Mesh, target: &Array<f64>) -> Result<(), &'static str> {
+    let supported = mesh.layers().iter().all(|layer| {
+        let layer = layer.lock().unwrap();
+        matches!(layer.attribute.cell_type(), CellType::Mlp | CellType::Rbf(_) | CellType::Som(..) | CellType::Custom(..))
+            && matches!(layer.attribute.normalization(), Normalization::None)
+    });
+    if !supported { return Err("Only Mlp, Rbf, Som and custom layers without normalization can be trained.") }
+    if target.elements() != *mesh.topology().last().unwrap() { return Err("Target does not match the output of the Node.") }
+    Ok(())
+}
+
+/// Derivative of an activation, given the activated values.
+fn derivative(activation: &Activation, activated: &Array<f64>) -> Array<f64> {
+    match activation {
+        Activation::Sigmoid => { mul(activated, &sub(&1.0, activated, false), false) }
+        Activation::TanH => { sub(&1.0, &mul(activated, activated, false), false) }
+        Activation::None => { constant(1.0, activated.dims()) }
+    }
+}
+
+/// Propagates the squared error of a pass back through the layers of a leaf, and descends its gradients by `rate`.
+///
+/// Weights and biases receive the gradients of the dense layers they feed, Rbf centers and widths those of their outputs.
+/// Som layers already updated their units during the pass and do not propagate gradients to the layers before them.
+/// Custom cells descend their own parameters in `Cell::backward`, every other parameter is updated once all gradients are known.
+///
+/// Returns the loss of the pass, half the sum of the squared differences between the output and `target`.
+///
+/// # Errors
+/// Returns an Err if a custom Cell does not implement backward, in which case the built-in parameters are left unchanged.
+pub(crate) fn backpropagate(mesh: &Mesh, traces: &[Trace], target: &Array<f64>, rate: f64) -> Result<f64, &'static str> {
+    let topology = mesh.topology();
+    let last = traces.len() - 1;
+    let error = sub(&traces[last].output, &moddims(target, traces[last].output.dims()), false);
+    let loss = 0.5 * sum_all(&mul(&error, &error, false)).0;
+
+    let mut gradients = topology.iter().map(|width| constant(0.0, dim4!(1, *width as u64, 1, 1))).collect::<Vec<Array<f64>>>();
+    gradients[last] = error;
+    let mut deltas: Vec<Option<Array<f64>>> = vec![None; traces.len()];
+    let mut updates: Vec<(usize, &str, Array<f64>)> = vec![];
+
+    for i in (0..traces.len()).rev() {
+        let mut layer = mesh.layers()[i].lock().unwrap();
+        // Gradients reaching the layer through the weights feeding the dense layers after it.
+        if let Some(weights) = layer.tensor.hash_map.get("_SYSTEM_WEIGHTS") {
+            let mut weight_gradient = constant(0.0, weights.dims());
+            let mut bias_gradient = constant(0.0, layer.tensor.hash_map.get("_SYSTEM_BIASES").unwrap().dims());
+            for j in (i + 1)..=layer.domain.end() {
+                let Some(delta) = &deltas[j] else { continue };
+                let block = (j - layer.domain.start()) as i32;
+                let weight_seq = [seq!(0, (topology[j] - 1) as i32, 1), seq!(), seq!(block, block, 1)];
+                let bias_seq = [seq!(0, (topology[j] - 1) as i32, 1), seq!(0, 0, 1), seq!(block, block, 1)];
+                gradients[i] = add(&gradients[i], &matmul(delta, &index(weights, &weight_seq), MatProp::NONE, MatProp::NONE), false);
+                assign_seq(&mut weight_gradient, &weight_seq, &matmul(delta, &traces[i].output, MatProp::TRANS, MatProp::NONE));
+                assign_seq(&mut bias_gradient, &bias_seq, &transpose(delta, false));
+            }
+            updates.push((i, "_SYSTEM_WEIGHTS", weight_gradient));
+            updates.push((i, "_SYSTEM_BIASES", bias_gradient));
+        }
+
+        let trace = &traces[i];
+        let mut delta = mul(&gradients[i], &derivative(layer.attribute.activation(), &trace.activated), false);
+        if let Some(mask) = &trace.mask { delta = mul(&delta, mask, false); }
+        match layer.attribute.cell_type() {
+            CellType::Mlp => { deltas[i] = Some(delta) }
+            CellType::Rbf(_) => {
+                let (input_gradient, center_gradient, width_gradient) = rbf_gradient(&layer.tensor, &trace.source, &delta);
+                gradients[i - 1] = add(&gradients[i - 1], &input_gradient, false);
+                updates.push((i, "_SYSTEM_RBF_CENTERS", center_gradient));
+                updates.push((i, "_SYSTEM_RBF_WIDTHS", width_gradient));
+            }
+            CellType::Custom(..) => {
+                let cell = layer.cell()?;
+                let input_gradient = cell.backward(&mut layer.tensor, &trace.source, &delta, rate)?;
+                // Dense cells pass the gradient on to the weights feeding them, all others to the previous layer.
+                if cell.is_dense() { deltas[i] = Some(input_gradient) } else { gradients[i - 1] = add(&gradients[i - 1], &input_gradient, false) }
+            }
+            _ => {}
+        }
+    }
+
+    updates.into_iter().for_each(|(i, key, gradient)| {
+        let mut layer = mesh.layers()[i].lock().unwrap();
+        let descended = sub(layer.tensor.hash_map.get(key).unwrap(), &mul(&gradient, &rate, false), false);
+        layer.tensor.insert(key, descended);
+    });
+    Ok(loss)
+}
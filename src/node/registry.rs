@@ -0,0 +1,41 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use std::sync::{Arc, OnceLock, RwLock};
+use rustc_hash::FxHashMap;
+
+/// Constructs an implementation of `T` from the configuration it recorded when it was saved.
+pub(crate) type Constructor<T> = fn(&str) -> Result<Box<T>, &'static str>;
+
+/// Constructors of implementations defined outside of the crate, registered by name.
+///
+/// Only the name and configuration of an implementation are saved, so it is rebuilt by the constructor registered under its name.
+pub(crate) struct Registry<T: ?Sized> {
+    constructors: OnceLock<RwLock<FxHashMap<String, Constructor<T>>>>,
+    missing: &'static str,
+}
+
+impl<T: ?Sized> Registry<T> {
+    /// Empty Registry, whose lookups of unregistered names fail with `missing`.
+    pub(crate) const fn new(missing: &'static str) -> Self {
+        Registry { constructors: OnceLock::new(), missing }
+    }
+
+    /// Registers a constructor by name, replacing any constructor previously registered under that name.
+    pub(crate) fn register(&self, name: &str, constructor: Constructor<T>) {
+        self.constructors.get_or_init(Default::default).write().unwrap().insert(name.to_string(), constructor);
+    }
+
+    /// Rebuilds a registered implementation from its name and configuration.
+    ///
+    /// # Errors
+    /// Returns an Err if no constructor is registered under `name`, or if the constructor rejects the configuration.
+    pub(crate) fn resolve(&self, name: &str, configuration: &str) -> Result<Arc<T>, &'static str> {
+        let constructor = *self.constructors.get_or_init(Default::default).read().unwrap().get(name).ok_or(self.missing)?;
+        Ok(Arc::from(constructor(configuration)?))
+    }
+}
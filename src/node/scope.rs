@@ -5,7 +5,8 @@
  * (c) Copyright by Christian Potts
  */
 
-#[derive(serde::Serialize, serde::Deserialize, Clone, Default)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, Default, Debug)]
+#[serde(default)]
 pub struct Scope{
     lower_bound: usize,
     upper_bound: usize,
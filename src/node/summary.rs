@@ -0,0 +1,229 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use std::fmt::{Display, Formatter};
+use uuid::Uuid;
+use crate::node::attribute::{Activation, Aggregation, CellType};
+use crate::node::layer::Layer;
+use crate::node::node::{Node, VertexKind};
+use crate::node::scope::Scope;
+use crate::node::tensor::Tensor;
+
+/// Report of the structure of a Network, built by `Network::summary`.
+///
+/// Nodes are listed depth first, each followed by its children column by column. Displaying the Summary pretty-prints it.
+pub struct Summary {
+    nodes: Vec<NodeSummary>,
+}
+
+impl Summary {
+    /// Summarizes the Node and its descendants.
+    pub(crate) fn new(node: &Node) -> Self {
+        let mut nodes = vec![];
+        Summary::collect(node, 0, None, &mut nodes);
+        Summary { nodes }
+    }
+
+    fn collect(node: &Node, depth: usize, position: Option<(usize, usize)>, nodes: &mut Vec<NodeSummary>) {
+        nodes.push(NodeSummary::new(node, depth, position));
+        node.children().iter().enumerate().for_each(|(column, children)| children.iter().enumerate()
+            .for_each(|(row, child)| Summary::collect(&child.lock().unwrap(), depth + 1, Some((column, row)), nodes)));
+    }
+
+    /// Acquire the summaries of the Nodes, depth first.
+    pub fn nodes(&self) -> &Vec<NodeSummary> {
+        &self.nodes
+    }
+
+    /// Counts the values of the learnable parameters of every Node.
+    pub fn parameters(&self) -> usize {
+        self.nodes.iter().map(|node| node.parameters()).sum()
+    }
+
+    /// Counts the bytes held by the Tensors of every Node.
+    pub fn memory(&self) -> usize {
+        self.nodes.iter().map(|node| node.memory()).sum()
+    }
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for node in &self.nodes {
+            let indent = "    ".repeat(node.depth);
+            let title = match &node.name {
+                Some(name) => { format!("{} ({})", name, node.uuid) }
+                None => { node.uuid.to_string() }
+            };
+            let position = node.position.map(|(column, row)| format!(" at ({}, {})", column, row)).unwrap_or_default();
+            writeln!(f, "{}{} {:?}{}, parameters: {}, memory: {} bytes", indent, title, node.kind, position, node.parameters(), node.memory)?;
+            writeln!(f, "{}    inputs: [{}]", indent, node.inputs.join(", "))?;
+            writeln!(f, "{}    outputs: [{}]", indent, node.outputs.join(", "))?;
+            for (index, layer) in node.layers.iter().enumerate() {
+                writeln!(f, "{}    layer {}: width {}, {:?}, {:?}, scope ({}, {}), parameters: {}", indent, index, layer.width, layer.cell_type, layer.activation,
+                         layer.scope.lower_bound(), layer.scope.upper_bound(), layer.parameters)?;
+            }
+        }
+        writeln!(f, "Total parameters: {}", self.parameters())?;
+        write!(f, "Total memory: {} bytes", self.memory())
+    }
+}
+
+/// Role of a summarized Node.
+#[derive(Clone, Debug, PartialEq)]
+pub enum NodeKind {
+    Leaf,
+    Vertex(VertexKind, Aggregation),
+    Function(String),
+}
+
+/// Report of a single Node.
+pub struct NodeSummary {
+    uuid: Uuid,
+    name: Option<String>,
+    kind: NodeKind,
+    depth: usize,
+    position: Option<(usize, usize)>,
+    layers: Vec<LayerSummary>,
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+    memory: usize,
+}
+
+impl NodeSummary {
+    fn new(node: &Node, depth: usize, position: Option<(usize, usize)>) -> Self {
+        let mesh = node.mesh();
+        let kind = match (node.function_name(), node.is_leaf_node()) {
+            (Some(name), _) => { NodeKind::Function(name.to_string()) }
+            (None, true) => { NodeKind::Leaf }
+            (None, false) => { NodeKind::Vertex(node.vertex_kind().cloned().unwrap_or_default(), mesh.layers()[1].lock().unwrap().attribute.aggregation().clone()) }
+        };
+        let topology = mesh.topology();
+        let layers = mesh.layers().iter().zip(topology).map(|(layer, width)| LayerSummary::new(&layer.lock().unwrap(), width)).collect();
+        let memory = mesh.layers().iter().map(|layer| bytes(&layer.lock().unwrap().tensor)).sum::<usize>() + bytes(&mesh.tensor());
+        let inputs = keys(&mesh.layers()[0].lock().unwrap().tensor);
+        let outputs = keys(&mesh.layers().last().unwrap().lock().unwrap().tensor);
+        NodeSummary {
+            uuid: node.uuid(),
+            name: node.name().map(str::to_string),
+            kind,
+            depth,
+            position,
+            layers,
+            inputs,
+            outputs,
+            memory,
+        }
+    }
+
+    /// Acquire the UUID of the Node.
+    pub fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    /// Acquire the name of the Node, if it has one.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Acquire the role of the Node.
+    pub fn kind(&self) -> &NodeKind {
+        &self.kind
+    }
+
+    /// Acquire the depth of the Node, the root being at depth 0.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Acquire the (column, row) position of the Node in its parent, None for the root.
+    pub fn position(&self) -> Option<(usize, usize)> {
+        self.position
+    }
+
+    /// Acquire the summaries of the Node's layers.
+    pub fn layers(&self) -> &Vec<LayerSummary> {
+        &self.layers
+    }
+
+    /// Acquire the keys of the entries received by the input layer, sorted.
+    pub fn inputs(&self) -> &Vec<String> {
+        &self.inputs
+    }
+
+    /// Acquire the keys of the entries held by the output layer, sorted.
+    pub fn outputs(&self) -> &Vec<String> {
+        &self.outputs
+    }
+
+    /// Counts the values of the learnable parameters of the Node's layers.
+    pub fn parameters(&self) -> usize {
+        self.layers.iter().map(|layer| layer.parameters).sum()
+    }
+
+    /// Counts the bytes held by the Node's layers and Mesh Tensor.
+    pub fn memory(&self) -> usize {
+        self.memory
+    }
+}
+
+/// Report of a single layer.
+pub struct LayerSummary {
+    width: usize,
+    activation: Activation,
+    cell_type: CellType,
+    scope: Scope,
+    parameters: usize,
+}
+
+impl LayerSummary {
+    fn new(layer: &Layer, width: usize) -> Self {
+        LayerSummary {
+            width,
+            activation: layer.attribute.activation().clone(),
+            cell_type: layer.attribute.cell_type().clone(),
+            scope: layer.attribute.scope().clone(),
+            parameters: layer.parameters(),
+        }
+    }
+
+    /// Acquire the size of the layer.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Acquire the Activation of the layer.
+    pub fn activation(&self) -> &Activation {
+        &self.activation
+    }
+
+    /// Acquire the CellType of the layer.
+    pub fn cell_type(&self) -> &CellType {
+        &self.cell_type
+    }
+
+    /// Acquire the Scope of the layer.
+    pub fn scope(&self) -> &Scope {
+        &self.scope
+    }
+
+    /// Counts the values of the layer's learnable parameters. Parameters are only counted once the layer is built.
+    pub fn parameters(&self) -> usize {
+        self.parameters
+    }
+}
+
+/// Returns the sorted keys of the Tensor's entries, leaving out system entries.
+fn keys(tensor: &Tensor) -> Vec<String> {
+    let mut keys = tensor.hash_map.keys().filter(|key| !key.contains("_SYSTEM")).cloned().collect::<Vec<String>>();
+    keys.sort();
+    keys
+}
+
+/// Counts the bytes held by the Tensor's entries.
+fn bytes(tensor: &Tensor) -> usize {
+    tensor.hash_map.values().map(|value| value.elements() * std::mem::size_of::<f64>()).sum()
+}
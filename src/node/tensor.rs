@@ -9,28 +9,44 @@ use arrayfire::Array;
 use rustc_hash::FxHashMap;
 use crate::node::edges::NodeRange;
 
+/// Named Arrayfire arrays, holding the inputs, parameters and state of a layer.
 #[derive(serde::Serialize, serde::Deserialize, Default)]
-pub(crate) struct Tensor{
+pub struct Tensor{
     pub(crate) hash_map: FxHashMap<String, Array<f64>>,
 }
 
 impl Tensor {
-    pub(crate) fn new(key_value_pair: &[(&str, Array<f64>)]) -> Tensor {
+    /// Creates a Tensor from named arrays.
+    pub fn new(key_value_pair: &[(&str, Array<f64>)]) -> Tensor {
         let mut hash_map = FxHashMap::default();
         for (k, v) in key_value_pair { hash_map.insert(k.to_string(), v.to_owned()); }
         Tensor { hash_map }
     }
 
-    pub(crate) fn insert(&mut self, key: &str, value: Array<f64>) {
+    /// Gets the array stored under `key`.
+    pub fn get(&self, key: &str) -> Option<&Array<f64>> {
+        self.hash_map.get(key)
+    }
+
+    /// Iterates over the named arrays, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Array<f64>)> {
+        self.hash_map.iter()
+    }
+
+    /// Stores `value` under `key`, replacing any previous array.
+    pub fn insert(&mut self, key: &str, value: Array<f64>) {
         self.hash_map.insert(key.to_string(), value);
     }
 
+    /// Renames the entries whose key is in `names`.
+    pub(crate) fn rename(&mut self, names: &FxHashMap<String, String>) {
+        self.hash_map = std::mem::take(&mut self.hash_map).into_iter().map(|(k, v)| (names.get(&k).cloned().unwrap_or(k), v)).collect();
+    }
+
+    /// Copies the entries of `tensor` selected by `node_range`.
+    ///
+    /// System entries hold the parameters and state of the layer owning them, so they are never copied.
     pub(crate) fn merge(&mut self, tensor: &Tensor, node_range: &NodeRange) {
-        tensor.hash_map.iter().for_each(|(k,v)|
-            match node_range {
-                NodeRange::Selective(key_chain) => { if key_chain.contains(k) { self.hash_map.insert(k.to_string(), v.to_owned()); } }
-                NodeRange::All => { self.hash_map.insert(k.to_string(), v.to_owned()); }
-            }
-        );
+        node_range.select(tensor).into_iter().filter(|(k, _)| !k.contains("_SYSTEM")).for_each(|(k, v)| { self.hash_map.insert(k, v); });
     }
 }
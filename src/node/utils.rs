@@ -5,44 +5,140 @@
  * (c) Copyright by Christian Potts
  */
 
+use std::fs;
 use std::mem;
 use std::ops::Mul;
-use arrayfire::{Array, assign_seq, constant, Dim4, dim4, randn, randu, seq};
-use rustc_hash::FxHashMap;
+use arrayfire::{Array, assign_seq, constant, Dim4, dim4, random_normal, random_uniform, RandomEngine, RandomEngineType, seq};
 use crate::node::attribute::TensorDescriptor;
 use crate::node::layer::TypeTensor;
 
-pub(crate) fn build_array(op: &TensorDescriptor, dims: arrayfire::Dim4) -> Array<f64> {
+/// Derives the seed of a random stream from a seed, a UUID and an index.
+///
+/// Layer streams are derived from the Network seed, the Node UUID and the layer index, so they do not depend on traversal order.
+/// The values are folded in that order with splitmix64, the UUID as its low then high 64 bits.
+/// As saved Networks are rebuilt from their seeds, the derivation is fixed and does not depend on the target or on dependencies.
+pub(crate) fn stream_seed(seed: u64, uuid: u128, index: u64) -> u64 {
+    [uuid as u64, (uuid >> 64) as u64, index].iter().fold(splitmix64(seed), |state, value| splitmix64(state ^ value))
+}
+
+/// Scrambles a 64-bit value with the splitmix64 finalizer.
+fn splitmix64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Creates a random stream from its seed.
+pub(crate) fn random_engine(seed: u64) -> RandomEngine {
+    RandomEngine::new(RandomEngineType::PHILOX_4X32_10, Some(seed))
+}
+
+pub(crate) fn build_array(op: &TensorDescriptor, dims: arrayfire::Dim4, engine: &RandomEngine) -> Result<Array<f64>, &'static str> {
     match &op {
-        TensorDescriptor::RandN =>  { randn(dims) }
-        TensorDescriptor::RandU =>  { randu(dims) }
-        TensorDescriptor::RangeN(rng)  => { (randn(dims) as Array<f64>).mul(*rng)}
-        TensorDescriptor::RangeU(rng) => { (randu(dims) as Array<f64>).mul(*rng)}
-        TensorDescriptor::Const(cnst) => { constant(*cnst, dims)}
-        _ => { constant(0.0, dims) }
+        TensorDescriptor::RandN =>  { Ok(random_normal(dims, engine)) }
+        TensorDescriptor::RandU =>  { Ok(random_uniform(dims, engine)) }
+        TensorDescriptor::RangeN(rng)  => { Ok((random_normal(dims, engine) as Array<f64>).mul(*rng)) }
+        TensorDescriptor::RangeU(rng) => { Ok((random_uniform(dims, engine) as Array<f64>).mul(*rng)) }
+        TensorDescriptor::Const(cnst) => { Ok(constant(*cnst, dims)) }
+        TensorDescriptor::Values(values, shape) => { explicit_array(values, shape, dims) }
+        TensorDescriptor::File(path) => { let (values, shape) = read_array_file(path)?; explicit_array(&values, &shape, dims) }
+        _ => { Ok(constant(0.0, dims)) }
     }
 }
 
-pub(crate) fn new_array(array_type: TypeTensor, vec: &mut Vec<usize>, base: &TensorDescriptor,
-                        op: &TensorDescriptor, start: usize, index: usize) -> Array<f64>{
+/// Builds an Arrayfire array from explicit column-major values.
+///
+/// The shape is padded with trailing 1's and has to match the dimensions computed from the layer topology.
+fn explicit_array(values: &[f64], shape: &[u64], dims: Dim4) -> Result<Array<f64>, &'static str> {
+    if shape.len() > 4 { return Err("Descriptor shape has more than four dimensions.") }
+    let mut padded = shape.to_vec();
+    padded.resize(4, 1);
+    if padded[..] != dims.get()[..] { return Err("Descriptor shape does not match the layer dimensions.") }
+    if values.len() as u64 != dims.elements() { return Err("Descriptor value count does not match its shape.") }
+    Ok(Array::new(values, dims))
+}
+
+/// Reads a `.npy` or comma separated file into column-major values and a shape.
+fn read_array_file(path: &str) -> Result<(Vec<f64>, Vec<u64>), &'static str> {
+    let bytes = fs::read(path).map_err(|_| "Unable to read descriptor file.")?;
+    if path.ends_with(".npy") { read_npy(&bytes) } else { read_csv(&bytes) }
+}
+
+/// Parses a version 1, 2 or 3 `.npy` file holding `<f8` or `<f4` values.
+fn read_npy(bytes: &[u8]) -> Result<(Vec<f64>, Vec<u64>), &'static str> {
+    if bytes.len() < 10 || &bytes[..6] != b"\x93NUMPY" { return Err("Descriptor file is not a valid npy file.") }
+    let (header_len, offset) = match bytes[6] {
+        1 => { (u16::from_le_bytes([bytes[8], bytes[9]]) as usize, 10) }
+        _ => {
+            if bytes.len() < 12 { return Err("Descriptor file is not a valid npy file.") }
+            (u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]) as usize, 12)
+        }
+    };
+    let header = bytes.get(offset..offset + header_len).and_then(|h| std::str::from_utf8(h).ok()).ok_or("Descriptor file has an invalid npy header.")?;
+    let descr = npy_header_value(header, "descr").ok_or("Descriptor file has an invalid npy header.")?;
+    let fortran_order = npy_header_value(header, "fortran_order").ok_or("Descriptor file has an invalid npy header.")?.starts_with("True");
+    let shape = npy_header_value(header, "shape").ok_or("Descriptor file has an invalid npy header.")?;
+    let shape = shape[shape.find('(').ok_or("Descriptor file has an invalid npy header.")? + 1..shape.find(')').ok_or("Descriptor file has an invalid npy header.")?]
+        .split(',').map(|e| e.trim()).filter(|e| !e.is_empty())
+        .map(|e| e.parse::<u64>().map_err(|_| "Descriptor file has an invalid npy header.")).collect::<Result<Vec<u64>, &'static str>>()?;
+    let data = &bytes[offset + header_len..];
+    let values = match descr.trim_matches(|c| c == '\'' || c == ' ') {
+        "<f8" => { data.chunks_exact(8).map(|c| f64::from_le_bytes([c[0], c[1], c[2], c[3], c[4], c[5], c[6], c[7]])).collect::<Vec<f64>>() }
+        "<f4" => { data.chunks_exact(4).map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]) as f64).collect::<Vec<f64>>() }
+        _ => { return Err("Descriptor file has an unsupported npy data type.") }
+    };
+    if values.len() as u64 != shape.iter().product::<u64>() { return Err("Descriptor value count does not match its shape.") }
+    Ok(if fortran_order { (values, shape) } else { (row_to_column_major(&values, &shape), shape) })
+}
+
+/// Returns the raw text following `'key':` inside of a npy header dictionary.
+fn npy_header_value<'a>(header: &'a str, key: &str) -> Option<&'a str> {
+    let start = header.find(&format!("'{}'", key))? + key.len() + 2;
+    let value = header[start..].trim_start().strip_prefix(':')?.trim_start();
+    let end = if value.starts_with('(') { value.find(')')? + 1 } else { value.find(',')? };
+    Some(&value[..end])
+}
+
+/// Parses a comma separated file, each line being a matrix row.
+fn read_csv(bytes: &[u8]) -> Result<(Vec<f64>, Vec<u64>), &'static str> {
+    let text = std::str::from_utf8(bytes).map_err(|_| "Descriptor file is not valid text.")?;
+    let rows = text.lines().filter(|line| !line.trim().is_empty())
+        .map(|line| line.split(',').map(|e| e.trim().parse::<f64>().map_err(|_| "Descriptor file contains an invalid number.")).collect::<Result<Vec<f64>, &'static str>>())
+        .collect::<Result<Vec<Vec<f64>>, &'static str>>()?;
+    let cols = rows.first().map(|row| row.len()).unwrap_or(0);
+    if rows.iter().any(|row| row.len() != cols) { return Err("Descriptor file rows are not of equal length.") }
+    let shape = vec![rows.len() as u64, cols as u64];
+    Ok((row_to_column_major(&rows.concat(), &shape), shape))
+}
+
+/// Reorders row-major values into the column-major layout used by Arrayfire.
+fn row_to_column_major(values: &[f64], shape: &[u64]) -> Vec<f64> {
+    let mut strides = vec![1u64; shape.len()];
+    (0..shape.len().saturating_sub(1)).rev().for_each(|i| strides[i] = strides[i + 1] * shape[i + 1]);
+    (0..values.len() as u64).map(|index| {
+        let (mut remainder, mut offset) = (index, 0);
+        shape.iter().zip(strides.iter()).for_each(|(dim, stride)| { offset += (remainder % dim) * stride; remainder /= dim; });
+        values[offset as usize]
+    }).collect()
+}
+
+pub(crate) fn new_array(array_type: TypeTensor, vec: &mut [usize], base: &TensorDescriptor,
+                        op: &TensorDescriptor, start: usize, index: usize, engine: &RandomEngine) -> Result<Array<f64>, &'static str> {
     let mut xx = 0;
     match &array_type{
-        TypeTensor::Weight => { mem::swap(&mut vec[(index - start) as usize], &mut xx); }
-        TypeTensor::Bias => { xx = 1; vec[(index - start) as usize] = 0; }
+        TypeTensor::Weight => { mem::swap(&mut vec[index - start], &mut xx); }
+        TypeTensor::Bias => { xx = 1; vec[index - start] = 0; }
+    }
+    if matches!(op, TensorDescriptor::Values(..) | TensorDescriptor::File(_)) && vec.iter().filter(|width| **width > 0).count() > 1 {
+        return Err("Explicit descriptor values require a Scope connecting the layer to a single other layer.")
     }
     let dims = Dim4::new(&[*vec.iter().max().unwrap() as u64,xx as u64,vec.len() as u64,1]);
-    let mut array = build_array(base, dims);
-    vec.iter().enumerate().filter(|e| *e.1 > 0).for_each(|e| {
+    let mut array = build_array(base, dims, engine)?;
+    for e in vec.iter().enumerate().filter(|e| *e.1 > 0) {
         let seq = &[seq!(0,(*e.1 - 1) as i32,1), seq!(0,(xx - 1) as i32,1), seq!(e.0 as i32,e.0 as i32,1)];
-        assign_seq(&mut array, seq , &build_array(op, dim4!(*e.1 as u64,xx as u64,1,1)));
-    });
+        assign_seq(&mut array, seq , &build_array(op, dim4!(*e.1 as u64,xx as u64,1,1), engine)?);
+    }
     array.eval();
-    array
+    Ok(array)
 }
-
-
-pub(crate) fn fx_hash_map_new<K: std::hash::Hash + std::cmp::Eq,V>(key_value_pair: Vec<(K, V)>) -> FxHashMap<K, V>{
-    let mut hash_map = FxHashMap::default();
-    for (k, v) in key_value_pair { hash_map.insert(k, v); }
-    hash_map
-}
\ No newline at end of file
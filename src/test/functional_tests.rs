@@ -5,9 +5,36 @@
  * (c) Copyright by Christian Potts
  */
 
+mod helpers;
 mod scope_tests;
 mod bias_tests;
 mod weight_tests;
 mod link_tests;
 mod feed_forward_tests;
-mod save_load_tests;
\ No newline at end of file
+mod save_load_tests;
+mod descriptor_tests;
+mod seed_tests;
+mod recurrent_tests;
+mod convolution_tests;
+mod pooling_tests;
+mod attention_tests;
+mod normalization_tests;
+mod dropout_tests;
+mod embedding_tests;
+mod radial_tests;
+mod training_tests;
+mod cell_tests;
+mod function_tests;
+mod aggregation_tests;
+mod gating_tests;
+mod routing_tests;
+mod structure_tests;
+mod lookup_tests;
+mod hot_swap_tests;
+mod clone_tests;
+mod dot_tests;
+mod summary_tests;
+mod builder_tests;
+mod config_tests;
+mod linking_tests;
+mod key_map_tests;
\ No newline at end of file
@@ -0,0 +1,98 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use arrayfire::{constant, dim4};
+use uuid::Uuid;
+use crate::node::attribute::{Activation, Aggregation, Attribute, CellType, TensorDescriptor};
+use crate::node::edges::{LinkType, NodeRange};
+use crate::node::processor::processor::Processor;
+use crate::node::mode::Mode;
+use crate::node::network::Network;
+use crate::node::node::{Node, NodeType, VertexKind};
+use crate::node::scope::Scope;
+use crate::node::tensor::Tensor;
+use crate::test::functional_tests::helpers::{host, weighted_leaf_node};
+
+fn aggregated(output: Attribute) -> (Uuid, Network) {
+    let uuid = Uuid::new_v4();
+    let network = Network::new(Node::new(uuid, NodeType::Vertex(VertexKind::Aggregate, Attribute::default(), weighted_leaf_node(&[1.0, 2.0]), output)));
+    network.node().write().unwrap().add_child_to_parent(weighted_leaf_node(&[3.0, 0.0]), None).unwrap();
+    network.node().write().unwrap().add_child_to_parent(weighted_leaf_node(&[2.0, 4.0]), None).unwrap();
+
+    let tensor = Tensor::new(&[("input", constant(1.0, dim4!(1,1,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+    (uuid, network)
+}
+
+fn output_values(aggregation: Aggregation) -> Vec<f64> {
+    let (uuid, network) = aggregated(Attribute::default().with_aggregation(aggregation));
+    let node = network.node();
+    let node = node.read().unwrap();
+    assert_eq!(1, node.mesh().tensor().hash_map.len());
+    host(node.mesh().tensor().hash_map.get(&uuid.to_string()).unwrap())
+}
+
+#[test]
+fn aggregation_concatenate() {
+    let (_, network) = aggregated(Attribute::default());
+    assert_eq!(6, network.node().read().unwrap().output().elements());
+}
+
+#[test]
+fn aggregation_element_wise() {
+    assert_eq!(vec![6.0, 6.0], output_values(Aggregation::Sum));
+    assert_eq!(vec![2.0, 2.0], output_values(Aggregation::Mean));
+    assert_eq!(vec![3.0, 4.0], output_values(Aggregation::Max));
+    assert_eq!(vec![0.0, 1.0], output_values(Aggregation::Vote));
+}
+
+#[test]
+fn aggregation_weighted_mean() {
+    let output = Attribute::new(
+        Activation::None,
+        CellType::Mlp,
+        vec![("_SYSTEM_AGGREGATION_WEIGHTS", TensorDescriptor::Values(vec![1.0, 0.0, 1.0], vec![3,1]))],
+        Scope::new(0,1)).with_aggregation(Aggregation::WeightedMean);
+    let (uuid, network) = aggregated(output);
+
+    assert_eq!(vec![1.5, 3.0], host(network.node().read().unwrap().mesh().tensor().hash_map.get(&uuid.to_string()).unwrap()));
+
+    // Explicit weights no longer match once a child is added.
+    network.node().write().unwrap().add_child_to_parent(weighted_leaf_node(&[1.0, 1.0]), None).unwrap();
+    let tensor = Tensor::new(&[("input", constant(1.0, dim4!(1,1,1,1)))]);
+    assert_eq!(Err("Descriptor shape does not match the layer dimensions."), network.node().read().unwrap().traverse(&tensor, true, Mode::Eval));
+}
+
+#[test]
+fn aggregation_weighted_mean_default() {
+    assert_eq!(vec![2.0, 2.0], output_values(Aggregation::WeightedMean));
+
+    let (uuid, network) = aggregated(Attribute::default().with_aggregation(Aggregation::WeightedMean));
+    network.node().write().unwrap().add_child_to_parent(weighted_leaf_node(&[6.0, 2.0]), None).unwrap();
+    let tensor = Tensor::new(&[("input", constant(1.0, dim4!(1,1,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+    assert_eq!(vec![3.0, 2.0], host(network.node().read().unwrap().mesh().tensor().hash_map.get(&uuid.to_string()).unwrap()));
+}
+
+#[test]
+fn aggregation_nested_weighted_mean() {
+    let weights = Attribute::new(
+        Activation::None,
+        CellType::Mlp,
+        vec![("_SYSTEM_AGGREGATION_WEIGHTS", TensorDescriptor::Values(vec![1.0, 3.0], vec![2,1]))],
+        Scope::new(0,1)).with_aggregation(Aggregation::WeightedMean);
+    let mut inner = Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, Attribute::default(), weighted_leaf_node(&[2.0]), weights));
+    inner.add_child_to_parent(weighted_leaf_node(&[4.0]), None).unwrap();
+    inner.mesh().edges().link(LinkType::OutputsToParent, NodeRange::All).unwrap();
+    let uuid = Uuid::new_v4();
+    let network = Network::new(Node::new(uuid, NodeType::Vertex(VertexKind::Aggregate, Attribute::default(), inner, Attribute::default().with_aggregation(Aggregation::WeightedMean))));
+
+    // The inner vertex's weights stay out of the outer vertex, which weighs its single child by the default weight.
+    let tensor = Tensor::new(&[("input", constant(1.0, dim4!(1,1,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+    assert_eq!(vec![3.5], host(network.node().read().unwrap().mesh().tensor().hash_map.get(&uuid.to_string()).unwrap()));
+}
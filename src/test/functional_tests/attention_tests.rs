@@ -0,0 +1,93 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use arrayfire::{Array, dim4};
+use uuid::Uuid;
+use crate::node::attribute::{Activation, Attribute, CellType, TensorDescriptor};
+use crate::node::processor::processor::Processor;
+use crate::node::mode::Mode;
+use crate::node::network::Network;
+use crate::node::node::{Node, NodeType};
+use crate::node::scope::Scope;
+use crate::node::tensor::Tensor;
+
+/// Query and key projections of zero attend uniformly, value and output projections of identity pass tokens through.
+fn averaging_projections(dimension: usize) -> Vec<f64> {
+    let mut values = vec![0.0; dimension * dimension * 4];
+    for projection in 2..4 {
+        (0..dimension).for_each(|i| values[projection * dimension * dimension + i * dimension + i] = 1.0);
+    }
+    values
+}
+
+/// Query, key, value and output projections of identity.
+fn identity_projections(dimension: usize) -> Vec<f64> {
+    let mut values = vec![0.0; dimension * dimension * 4];
+    for projection in 0..4 {
+        (0..dimension).for_each(|i| values[projection * dimension * dimension + i * dimension + i] = 1.0);
+    }
+    values
+}
+
+fn attention_network(dimension: usize, heads: usize) -> Network {
+    projected_network(dimension, heads, averaging_projections(dimension))
+}
+
+fn projected_network(dimension: usize, heads: usize, projections: Vec<f64>) -> Network {
+    let input = Attribute::new(Activation::None, CellType::Mlp, vec![], Scope::new(0,1));
+    let attention = Attribute::new(
+        Activation::None,
+        CellType::Attention(dimension, heads),
+        vec![("_SYSTEM_ATTENTION_WEIGHTS", TensorDescriptor::Values(projections, vec![dimension as u64, dimension as u64, 4])),
+             ("_SYSTEM_ATTENTION_BIASES", TensorDescriptor::Const(0.0))],
+        Scope::new(0,1));
+    Network::new(Node::new(Uuid::new_v4(), NodeType::Leaf(input, vec![(attention, 0)])))
+}
+
+#[test]
+fn attention_topology() {
+    let network = attention_network(4, 2);
+    let tensor = Tensor::new(&[("input", Array::new_empty(dim4!(1,12,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+
+    assert_eq!(vec![12, 12], network.node().read().unwrap().mesh().topology());
+}
+
+#[test]
+fn attention_over_named_inputs() {
+    let network = attention_network(4, 2);
+    let tensor = Tensor::new(&[("token_a", Array::new(&[1.0, 2.0, 3.0, 4.0], dim4!(1,4,1,1))),
+                               ("token_b", Array::new(&[5.0, 6.0, 7.0, 8.0], dim4!(1,4,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+
+    let mut host = vec![0.0; 16];
+    network.node().read().unwrap().mesh().tensor().hash_map.get("_SYSTEM_VALUES").unwrap().host(&mut host);
+    assert_eq!(vec![3.0, 4.0, 5.0, 6.0, 3.0, 4.0, 5.0, 6.0], host[8..].to_vec());
+}
+
+#[test]
+fn attention_heads_attend_separately() {
+    let network = projected_network(2, 2, identity_projections(2));
+    let tensor = Tensor::new(&[("input", Array::new(&[1.0, 0.0, 0.0, 2.0], dim4!(1,4,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+
+    // Each head sees one component of the tokens (1, 0) and (0, 2), with a scale of 1 as heads are of size 1.
+    // The first head scores [[1, 0], [0, 0]] and the second [[0, 0], [0, 4]], each row being weighted by its softmax.
+    let e = 1.0f64.exp();
+    let e4 = 4.0f64.exp();
+    let expected = [e / (e + 1.0), 1.0, 0.5, 2.0 * e4 / (e4 + 1.0)];
+    let mut host = vec![0.0; 8];
+    network.node().read().unwrap().mesh().tensor().hash_map.get("_SYSTEM_VALUES").unwrap().host(&mut host);
+    host[4..].iter().zip(expected.iter()).for_each(|(value, expected)| assert!((value - expected).abs() < 1e-9));
+}
+
+#[test]
+fn attention_heads_mismatch() {
+    let network = attention_network(4, 3);
+    let tensor = Tensor::new(&[("input", Array::new_empty(dim4!(1,8,1,1)))]);
+    assert_eq!(Err("Attention dimension is not divisible into heads."), network.node().read().unwrap().traverse(&tensor, true, Mode::Eval));
+}
@@ -13,6 +13,7 @@ use arrayfire::{Array, assign_seq, constant, Dim4, index, seq};
 use uuid::Uuid;
 use crate::node::attribute::{Activation, Attribute, CellType, TensorDescriptor};
 use crate::node::processor::processor::Processor;
+use crate::node::mode::Mode;
 use crate::node::network::Network;
 use crate::node::node::{Node, NodeType};
 use crate::node::scope::Scope;
@@ -40,7 +41,7 @@ fn create_biases_0_1(){
         )));
 
     let tensor = Tensor::new(&[("input", Array::new_empty(Dim4::new(&[1,2,1,1])))]);
-    network.node().read().unwrap().traverse(&tensor, true);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
     let test_biases_cutouts : Array<f64> = constant(1.0, Dim4::new(&[4,4,4,1]));
 
     let mut b_test_0 : Array<f64> = constant(0.0, network.node().read().unwrap().mesh().layers()[0].lock().unwrap().tensor.hash_map.get("_SYSTEM_BIASES").unwrap().dims());
@@ -98,7 +99,7 @@ fn create_biases_0_2(){
 
 
     let tensor = Tensor::new(&[("input", Array::new_empty(Dim4::new(&[1,2,1,1])))]);
-    network.node().read().unwrap().traverse(&tensor, true);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
     let test_biases_cutouts : Array<f64> = constant(1.0, Dim4::new(&[4,4,4,1]));
 
     let z_seq_0 = seq!(1,1,1);
@@ -165,7 +166,7 @@ fn create_biases_1_1(){
         )));
 
     let tensor = Tensor::new(&[("input", Array::new_empty(Dim4::new(&[1,2,1,1])))]);
-    network.node().read().unwrap().traverse(&tensor, true);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
     let test_biases_cutouts : Array<f64> = constant(1.0, Dim4::new(&[4,4,4,1]));
 
     let z_seq_0 = seq!(0,0,1);
@@ -238,7 +239,7 @@ fn create_biases_2_2(){
         )));
 
     let tensor = Tensor::new(&[("input", Array::new_empty(Dim4::new(&[1,2,1,1])))]);
-    network.node().read().unwrap().traverse(&tensor, true);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
     let test_biases_cutouts : Array<f64> = constant(1.0, Dim4::new(&[5,5,5,1]));
 
     let z_seq_0 = seq!(0,0,1);
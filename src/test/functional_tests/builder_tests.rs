@@ -0,0 +1,86 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use uuid::Uuid;
+use crate::node::attribute::Attribute;
+use crate::node::builder::NodeBuilder;
+use crate::node::edges::NodeRange;
+use crate::node::node::{Routing, VertexKind};
+
+fn leaf(name: &str) -> NodeBuilder {
+    let attribute = Attribute::default();
+    NodeBuilder::leaf(name, attribute.clone()).layer(attribute, 2)
+}
+
+fn vertex(name: &str) -> NodeBuilder {
+    NodeBuilder::vertex(name, Attribute::default(), Attribute::default())
+}
+
+#[test]
+fn build_places_children() {
+    let uuid = Uuid::new_v4();
+    let root = vertex("root")
+        .child(leaf("first"))
+        .child_in(0, leaf("second").uuid(uuid))
+        .child(vertex("nested").child(leaf("third")))
+        .build()
+        .unwrap();
+
+    assert_eq!(Some("root"), root.name());
+    assert_eq!(vec![2, 1], root.children().iter().map(|column| column.len()).collect::<Vec<usize>>());
+    assert_eq!(uuid, root.index_into_node(&[(0,1)]).unwrap().lock().unwrap().uuid());
+    assert!(root.find_by_path("nested/third").is_some());
+}
+
+#[test]
+fn build_links_by_name() {
+    let root = vertex("root")
+        .child(leaf("encoder").inputs_from_parent(NodeRange::Selective(vec!["input".to_string()])))
+        .child(leaf("decoder").inputs_from_peer("encoder", NodeRange::All).outputs_to_parent(NodeRange::All))
+        .build()
+        .unwrap();
+
+    let decoder = root.find_by_path("decoder").unwrap();
+    let decoder = decoder.lock().unwrap();
+    let mesh = decoder.mesh();
+    let edges = mesh.edges();
+    assert_eq!(vec![(0,0)], edges.input_from_peer_output.iter().map(|(position, _)| *position).collect::<Vec<(usize, usize)>>());
+    assert!(matches!(edges.output_to_parent, NodeRange::All));
+
+    let encoder = root.find_by_path("encoder").unwrap();
+    assert!(matches!(encoder.lock().unwrap().mesh().edges().input_from_parent, NodeRange::Selective(_)));
+}
+
+#[test]
+fn build_validation() {
+    let attribute = Attribute::default();
+    assert!(NodeBuilder::leaf("leaf", attribute).build().is_err());
+    assert!(vertex("root").build().is_err());
+    assert!(vertex("root").child_in(1, leaf("leaf")).build().is_err());
+    assert!(vertex("root").child(leaf("leaf")).child(leaf("leaf")).build().is_err());
+    assert!(vertex("root").child(leaf("leaf").inputs_from_peer("missing", NodeRange::All)).build().is_err());
+    assert!(vertex("root").child(leaf("leaf").inputs_from_peer("leaf", NodeRange::All)).build().is_err());
+    assert!(leaf("leaf").inputs_from_peer("other", NodeRange::All).build().is_err());
+}
+
+#[test]
+fn build_rejects_misuse() {
+    let attribute = Attribute::default();
+    assert_eq!(Err("Only a leaf has layers."), vertex("root").child(leaf("leaf")).layer(attribute.clone(), 2).build().map(|_| ()));
+    assert_eq!(Err("Only a vertex has children."), leaf("leaf").child(leaf("child")).build().map(|_| ()));
+    assert_eq!(Err("Only a vertex has children."), vertex("root").child(leaf("leaf").child_in(0, leaf("child"))).build().map(|_| ()));
+    assert_eq!(Err("Only a vertex has a kind."), leaf("leaf").vertex_kind(VertexKind::Routed(Routing::Best)).build().map(|_| ()));
+}
+
+#[test]
+fn build_checks_links() {
+    let reserved = NodeRange::Selective(vec!["_SYSTEM_VALUES".to_string()]);
+    assert_eq!(Err("Linked key is reserved."), leaf("leaf").inputs_from_parent(reserved).build().map(|_| ()));
+    assert_eq!(Err("Linked key is not an output of the linked leaf."), leaf("leaf").outputs_to_parent(NodeRange::Selective(vec!["missing".to_string()])).build().map(|_| ()));
+    let duplicate = leaf("decoder").inputs_from_peer("encoder", NodeRange::All).inputs_from_peer("encoder", NodeRange::All);
+    assert_eq!(Err("Node already reads the outputs of the linked peer."), vertex("root").child(leaf("encoder")).child(duplicate).build().map(|_| ()));
+}
@@ -0,0 +1,151 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use std::fs;
+use arrayfire::{Array, constant, Dim4, dim4};
+use uuid::Uuid;
+use crate::node::attribute::{Activation, Attribute, CellType, TensorDescriptor};
+use crate::node::cell::{Cell, register_cell};
+use crate::node::processor::processor::Processor;
+use crate::node::mode::Mode;
+use crate::node::network::Network;
+use crate::node::node::{Node, NodeType};
+use crate::node::scope::Scope;
+use crate::node::tensor::Tensor;
+use crate::test::functional_tests::helpers::{dense_attribute, host, layer_values};
+
+/// Repeats the previous layer `repeat` times, adding a learnable offset and the number of previous passes.
+struct Repeat {
+    repeat: usize,
+}
+
+impl Cell for Repeat {
+    fn name(&self) -> &str { "repeat" }
+
+    fn configuration(&self) -> String { self.repeat.to_string() }
+
+    fn output_width(&self, width: usize) -> Result<Option<usize>, &'static str> { Ok(Some(width * self.repeat)) }
+
+    fn parameters(&self, width: usize, _previous: usize) -> Vec<(String, Dim4)> { vec![("_SYSTEM_OFFSET".to_string(), dim4!(1, width as u64, 1, 1))] }
+
+    fn state(&self, _width: usize, _previous: usize) -> Vec<(String, Dim4)> { vec![("_SYSTEM_PASSES".to_string(), dim4!(1, 1, 1, 1))] }
+
+    fn forward(&self, tensor: &mut Tensor, input: Array<f64>, _mode: Mode) -> Array<f64> {
+        let passes = tensor.get("_SYSTEM_PASSES").unwrap().clone();
+        tensor.insert("_SYSTEM_PASSES", &passes + 1.0);
+        let repeated = arrayfire::tile(&input, dim4!(1, self.repeat as u64, 1, 1));
+        arrayfire::add(&arrayfire::add(&repeated, tensor.get("_SYSTEM_OFFSET").unwrap(), false), &passes, true)
+    }
+}
+
+/// Scales the previous layer by a learnable factor per unit.
+struct Scale;
+
+impl Cell for Scale {
+    fn name(&self) -> &str { "scale" }
+
+    fn parameters(&self, width: usize, _previous: usize) -> Vec<(String, Dim4)> { vec![("_SYSTEM_SCALE".to_string(), dim4!(1, width as u64, 1, 1))] }
+
+    fn forward(&self, tensor: &mut Tensor, input: Array<f64>, _mode: Mode) -> Array<f64> {
+        arrayfire::mul(&input, tensor.get("_SYSTEM_SCALE").unwrap(), false)
+    }
+
+    fn backward(&self, tensor: &mut Tensor, input: &Array<f64>, gradient: &Array<f64>, rate: f64) -> Result<Array<f64>, &'static str> {
+        let scale = tensor.get("_SYSTEM_SCALE").unwrap().clone();
+        tensor.insert("_SYSTEM_SCALE", &scale - &(arrayfire::mul(gradient, input, false) * rate));
+        Ok(arrayfire::mul(gradient, &scale, false))
+    }
+}
+
+fn register() {
+    register_cell("repeat", |configuration| {
+        let repeat = configuration.parse::<usize>().map_err(|_| "Invalid repeat configuration.")?;
+        Ok(Box::new(Repeat { repeat }))
+    });
+    register_cell("scale", |_| Ok(Box::new(Scale)));
+}
+
+fn scale_network() -> Network {
+    let scale = Attribute::new(Activation::None, CellType::custom(&Scale), vec![("_SYSTEM_SCALE", TensorDescriptor::Const(0.5))], Scope::new(0,1));
+    Network::new(Node::new(Uuid::new_v4(), NodeType::Leaf(dense_attribute(), vec![(scale, 1)])))
+}
+
+fn custom_network(uuid: Uuid) -> Network {
+    let repeat = Attribute::new(
+        Activation::None,
+        CellType::custom(&Repeat { repeat: 2 }),
+        vec![("_SYSTEM_OFFSET", TensorDescriptor::Const(0.5)), ("_SYSTEM_WEIGHTS", TensorDescriptor::Const(1.0)), ("_SYSTEM_BIASES", TensorDescriptor::Const(0.0))],
+        Scope::new(0,1));
+    Network::new(Node::new(uuid, NodeType::Leaf(dense_attribute(), vec![(repeat, 0), (dense_attribute(), 1)])))
+}
+
+#[test]
+fn custom_cell_mixed_with_builtin() {
+    register();
+    let network = custom_network(Uuid::new_v4());
+    let tensor = Tensor::new(&[("input", Array::new(&[1.0, 2.0], dim4!(1,2,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+
+    assert_eq!(vec![2, 4, 1], network.node().read().unwrap().mesh().topology());
+    assert_eq!(vec![1.5, 2.5, 1.5, 2.5], layer_values(&network, 1));
+    assert_eq!(vec![8.0], layer_values(&network, 2));
+
+    network.node().read().unwrap().traverse(&tensor, false, Mode::Eval).unwrap();
+    assert_eq!(vec![2.5, 3.5, 2.5, 3.5], layer_values(&network, 1));
+
+    network.node().read().unwrap().reset_state();
+    network.node().read().unwrap().traverse(&tensor, false, Mode::Eval).unwrap();
+    assert_eq!(vec![1.5, 2.5, 1.5, 2.5], layer_values(&network, 1));
+}
+
+#[test]
+fn custom_cell_save_load() {
+    register();
+    let network = custom_network(Uuid::from_u128(36));
+    let tensor = Tensor::new(&[("input", constant(1.0, dim4!(1,1,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+    network.save();
+
+    let network = Network::load("00000000-0000-0000-0000-000000000024.annml").unwrap();
+    fs::remove_file("00000000-0000-0000-0000-000000000024.annml").unwrap();
+    network.node().read().unwrap().traverse(&tensor, false, Mode::Eval).unwrap();
+    assert_eq!(vec![2.5, 2.5], layer_values(&network, 1));
+}
+
+#[test]
+fn custom_cell_unregistered() {
+    let attribute = Attribute::new(Activation::None, CellType::Custom("unregistered".to_string(), String::new()), vec![], Scope::new(0,1));
+    let network = Network::new(Node::new(Uuid::new_v4(), NodeType::Leaf(dense_attribute(), vec![(attribute, 0)])));
+    let tensor = Tensor::new(&[("input", constant(1.0, dim4!(1,1,1,1)))]);
+    assert_eq!(Err("Cell is not registered."), network.node().read().unwrap().traverse(&tensor, true, Mode::Eval));
+}
+
+#[test]
+fn custom_cell_backward() {
+    register();
+    let network = scale_network();
+    let tensor = Tensor::new(&[("input", Array::new(&[2.0], dim4!(1,1,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+
+    // The output 1 misses the target by -2, so the scale moves by 0.1 * 2 * 2.
+    let loss = network.node().read().unwrap().train(&tensor, &Array::new(&[3.0], dim4!(1,1,1,1)), 0.1).unwrap();
+    assert_eq!(2.0, loss);
+    let node = network.node();
+    let node = node.read().unwrap();
+    let mesh = node.mesh();
+    let layer = mesh.layers()[1].lock().unwrap();
+    assert!((host(layer.tensor.get("_SYSTEM_SCALE").unwrap())[0] - 0.9).abs() < 1e-12);
+}
+
+#[test]
+fn custom_cell_without_backward() {
+    register();
+    let network = custom_network(Uuid::new_v4());
+    let tensor = Tensor::new(&[("input", constant(1.0, dim4!(1,1,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+    assert_eq!(Err("Cell does not implement backward."), network.node().read().unwrap().train(&tensor, &constant(1.0, dim4!(1,1,1,1)), 0.1));
+}
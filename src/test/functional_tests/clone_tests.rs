@@ -0,0 +1,86 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use arrayfire::{constant, dim4};
+use uuid::Uuid;
+use crate::node::attribute::{Activation, Attribute, CellType, TensorDescriptor};
+use crate::node::processor::processor::Processor;
+use crate::node::mode::Mode;
+use crate::node::node::{CloneWeights, Node, NodeType, VertexKind};
+use crate::node::scope::Scope;
+use crate::node::tensor::Tensor;
+use crate::test::functional_tests::helpers::host;
+
+/// Leaf whose input layer draws random weights.
+fn random_leaf_node() -> Node {
+    let input = Attribute::new(
+        Activation::None,
+        CellType::Mlp,
+        vec![("_SYSTEM_WEIGHTS", TensorDescriptor::RandN), ("_SYSTEM_BIASES", TensorDescriptor::Const(0.0))],
+        Scope::new(0,1));
+    Node::new(Uuid::new_v4(), NodeType::Leaf(input, vec![(Attribute::default(), 2)]))
+}
+
+fn input() -> Tensor {
+    Tensor::new(&[("input", constant(1.0, dim4!(1,1,1,1)))])
+}
+
+fn weights(node: &Node) -> Vec<f64> {
+    let mesh = node.mesh();
+    let layer = mesh.layers()[0].lock().unwrap();
+    host(layer.tensor.get("_SYSTEM_WEIGHTS").unwrap())
+}
+
+#[test]
+fn deep_clone_identical() {
+    let node = random_leaf_node();
+    node.traverse(&input(), true, Mode::Eval).unwrap();
+
+    let copy = node.deep_clone(CloneWeights::Identical, false).unwrap();
+    assert_eq!(node.uuid(), copy.uuid());
+    assert_eq!(weights(&node), weights(&copy));
+    copy.traverse(&input(), false, Mode::Eval).unwrap();
+    assert_eq!(host(&node.output()), host(&copy.output()));
+
+    // The copy does not share its Mesh with the Node.
+    copy.mesh().layers()[0].lock().unwrap().tensor.insert("_SYSTEM_WEIGHTS", constant(0.0, dim4!(1,2,1,1)));
+    assert_ne!(weights(&node), weights(&copy));
+}
+
+#[test]
+fn deep_clone_regenerates_uuids() {
+    let attribute = Attribute::default();
+    let mut vertex = Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, attribute.clone(), random_leaf_node(), attribute));
+    vertex.traverse(&input(), true, Mode::Eval).unwrap();
+    let previous = vertex.children()[0][0].lock().unwrap().uuid();
+
+    let copy = vertex.children()[0][0].lock().unwrap().deep_clone(CloneWeights::Identical, true).unwrap();
+    assert_ne!(previous, copy.uuid());
+    let mesh = copy.mesh();
+    assert!(mesh.layers().iter().all(|layer| layer.lock().unwrap().tensor.get(&previous.to_string()).is_none()));
+    assert!(copy.mesh().tensor().get(&copy.uuid().to_string()).is_some());
+
+    // Both outputs reach the parent.
+    vertex.add_child_to_parent(copy, None).unwrap();
+    vertex.traverse(&input(), true, Mode::Eval).unwrap();
+    assert_eq!(4, vertex.output().elements());
+}
+
+#[test]
+fn deep_clone_reinitialized() {
+    let node = random_leaf_node();
+    node.traverse(&input(), true, Mode::Eval).unwrap();
+
+    // Copies are reseeded from the Node, so they draw new values whether or not their UUIDs are regenerated.
+    let copy = node.deep_clone(CloneWeights::Reinitialized, false).unwrap();
+    assert_eq!(node.uuid(), copy.uuid());
+    assert_ne!(weights(&node), weights(&copy));
+    assert_ne!(weights(&node), weights(&node.deep_clone(CloneWeights::Reinitialized, true).unwrap()));
+
+    // Copies keeping the Node's UUIDs derive the same seed, so they draw the same values.
+    assert_eq!(weights(&copy), weights(&node.deep_clone(CloneWeights::Reinitialized, false).unwrap()));
+}
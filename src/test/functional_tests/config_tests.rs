@@ -0,0 +1,140 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use arrayfire::{constant, dim4};
+use uuid::Uuid;
+use crate::node::attribute::{Activation, Attribute, CellType, Normalization, TensorDescriptor};
+use crate::node::builder::NodeBuilder;
+use crate::node::edges::NodeRange;
+use crate::node::mode::Mode;
+use crate::node::network::Network;
+use crate::node::node::{Routing, Selection, VertexKind};
+use crate::node::processor::processor::Processor;
+use crate::node::scope::Scope;
+use crate::node::tensor::Tensor;
+
+const DEFINITION: &str = r#"
+    seed = 3
+
+    [root]
+    name = "root"
+
+    [[root.columns]]
+    [[root.columns.children]]
+    name = "encoder"
+    inputs_from_parent = { Selective = ["input"] }
+    input = { activation = "None", scope = { upper_bound = 1 }, descriptor = { _SYSTEM_WEIGHTS = "RandU" } }
+    layers = [{ count = 3, attribute = { normalization = "Layer" } }]
+
+    [[root.columns.children]]
+    layers = [{ count = 2 }]
+
+    [[root.columns]]
+    [[root.columns.children]]
+    name = "decoder"
+    layers = [{ count = 2 }]
+    outputs_to_parent = "All"
+    inputs_from_peers = [{ peer = "encoder", range = "All" }]
+"#;
+
+#[test]
+fn from_config_structure() {
+    let network = Network::from_config_str(DEFINITION).unwrap();
+    assert_eq!(3, network.seed());
+
+    let root = network.node();
+    let root = root.read().unwrap();
+    assert_eq!(vec![2, 1], root.children().iter().map(|column| column.len()).collect::<Vec<usize>>());
+    assert!(root.index_into_node(&[(0,1)]).unwrap().lock().unwrap().name().is_none());
+
+    {
+        let encoder = network.find_by_path("root/encoder").unwrap();
+        let encoder = encoder.lock().unwrap();
+        let mesh = encoder.mesh();
+        assert!(matches!(mesh.edges().input_from_parent, NodeRange::Selective(_)));
+        let input = mesh.layers()[0].lock().unwrap();
+        assert!(matches!(input.attribute.activation(), Activation::None));
+        assert_eq!(1, input.attribute.scope().upper_bound());
+        assert!(matches!(input.attribute.description("_SYSTEM_WEIGHTS"), TensorDescriptor::RandU));
+        // The biases the scope requires are left out of the definition.
+        assert!(matches!(input.attribute.description("_SYSTEM_BIASES"), TensorDescriptor::RandN));
+        assert!(matches!(mesh.layers()[1].lock().unwrap().attribute.normalization(), Normalization::Layer));
+    }
+
+    let decoder = network.find_by_path("root/decoder").unwrap();
+    let decoder = decoder.lock().unwrap();
+    assert_eq!(vec![(0,0)], decoder.mesh().edges().input_from_peer_output.iter().map(|(position, _)| *position).collect::<Vec<(usize, usize)>>());
+}
+
+#[test]
+fn to_config_round_trip() {
+    let attribute = Attribute::new(Activation::TanH, CellType::Mlp, vec![("_SYSTEM_WEIGHTS", TensorDescriptor::Const(0.5)), ("_SYSTEM_BIASES", TensorDescriptor::RandN)], Scope::new(0,1));
+    let uuid = Uuid::new_v4();
+    let root = NodeBuilder::vertex("root", Attribute::default(), Attribute::default())
+        .child(NodeBuilder::leaf("encoder", attribute.clone()).layer(attribute, 3).uuid(uuid))
+        .child(NodeBuilder::leaf("decoder", Attribute::default()).layer(Attribute::default(), 2).inputs_from_peer("encoder", NodeRange::All))
+        .build()
+        .unwrap();
+    let network = Network::with_seed(root, 5);
+
+    let definition = network.to_config().unwrap();
+    let copy = Network::from_config_str(&definition).unwrap();
+    assert_eq!(definition, copy.to_config().unwrap());
+    assert_eq!(Some("encoder"), copy.find_by_uuid(uuid).unwrap().lock().unwrap().name());
+}
+
+#[test]
+fn from_config_runs_and_round_trips() {
+    let network = Network::from_config_str(DEFINITION).unwrap();
+    let definition = network.to_config().unwrap();
+
+    let tensor = Tensor::new(&[("input", constant(1.0, dim4!(1,2,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+    network.node().read().unwrap().traverse(&tensor, false, Mode::Eval).unwrap();
+    assert_eq!(3, network.find_by_path("root/encoder").unwrap().lock().unwrap().output().elements());
+
+    assert_eq!(definition, network.to_config().unwrap());
+    assert_eq!(definition, Network::from_config_str(&definition).unwrap().to_config().unwrap());
+}
+
+#[test]
+fn to_config_vertex_kinds() {
+    let gated = NodeBuilder::vertex("gated", Attribute::default(), Attribute::default())
+        .vertex_kind(VertexKind::Gated(Selection::TopK(1)))
+        .child(NodeBuilder::leaf("gate", Attribute::default()).layer(Attribute::default(), 2))
+        .child(NodeBuilder::leaf("expert", Attribute::default()).layer(Attribute::default(), 1));
+    let root = NodeBuilder::vertex("root", Attribute::default(), Attribute::default())
+        .vertex_kind(VertexKind::Routed(Routing::Threshold(0.5)))
+        .child(NodeBuilder::leaf("router", Attribute::default()).layer(Attribute::default(), 1))
+        .child_in(1, gated)
+        .build()
+        .unwrap();
+
+    let definition = Network::new(root).to_config().unwrap();
+    let copy = Network::from_config_str(&definition).unwrap();
+    assert_eq!(definition, copy.to_config().unwrap());
+    assert_eq!(Some(&VertexKind::Routed(Routing::Threshold(0.5))), copy.node().read().unwrap().vertex_kind());
+    assert_eq!(Some(&VertexKind::Gated(Selection::TopK(1))), copy.find_by_path("root/gated").unwrap().lock().unwrap().vertex_kind());
+}
+
+#[test]
+fn from_config_file() {
+    let path = std::env::temp_dir().join(format!("{}.toml", Uuid::new_v4()));
+    std::fs::write(&path, DEFINITION).unwrap();
+    let network = Network::from_config(path.to_str().unwrap());
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(network.unwrap().find_by_path("root/encoder").is_some());
+    assert!(Network::from_config("missing.toml").is_err());
+}
+
+#[test]
+fn from_config_invalid() {
+    assert!(Network::from_config_str("[root").is_err());
+    assert!(Network::from_config_str("[root]\nname = \"root\"").is_err());
+    assert!(Network::from_config_str(&DEFINITION.replace("peer = \"encoder\"", "peer = \"missing\"")).is_err());
+}
@@ -0,0 +1,80 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use arrayfire::{Array, constant, dim4};
+use uuid::Uuid;
+use crate::node::attribute::{Activation, Attribute, CellType, TensorDescriptor};
+use crate::node::kernel::Kernel;
+use crate::node::processor::processor::Processor;
+use crate::node::mode::Mode;
+use crate::node::network::Network;
+use crate::node::node::{Node, NodeType};
+use crate::node::scope::Scope;
+use crate::node::tensor::Tensor;
+use crate::test::functional_tests::helpers::{dense_attribute, layer_values};
+
+fn conv_attribute(cell_type: CellType) -> Attribute {
+    Attribute::new(
+        Activation::None,
+        cell_type,
+        vec![("_SYSTEM_KERNELS", TensorDescriptor::Const(1.0)), ("_SYSTEM_KERNEL_BIASES", TensorDescriptor::Const(0.5)),
+             ("_SYSTEM_WEIGHTS", TensorDescriptor::Const(1.0)), ("_SYSTEM_BIASES", TensorDescriptor::Const(0.0))],
+        Scope::new(0,1))
+}
+
+#[test]
+fn conv1d_topology() {
+    let conv = conv_attribute(CellType::Conv1d(Kernel::new_1d(3, 1, 0, (1, 2))));
+    let network = Network::new(Node::new(Uuid::new_v4(), NodeType::Leaf(dense_attribute(), vec![(conv, 0), (dense_attribute(), 3)])));
+
+    let tensor = Tensor::new(&[("input", Array::new_empty(dim4!(1,6,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+
+    assert_eq!(vec![6, 8, 3], network.node().read().unwrap().mesh().topology());
+}
+
+#[test]
+fn conv1d_values() {
+    let conv = conv_attribute(CellType::Conv1d(Kernel::new_1d(3, 1, 0, (1, 2))));
+    let network = Network::new(Node::new(Uuid::new_v4(), NodeType::Leaf(dense_attribute(), vec![(conv, 0)])));
+
+    let tensor = Tensor::new(&[("input", Array::new(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0], dim4!(1,6,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+
+    assert_eq!(vec![6.5, 9.5, 12.5, 15.5, 6.5, 9.5, 12.5, 15.5], layer_values(&network, 1));
+}
+
+#[test]
+fn conv2d_stride_padding_values() {
+    let conv = conv_attribute(CellType::Conv2d(Kernel::new((3,3), (2,2), (1,1), (1,1), 4)));
+    let network = Network::new(Node::new(Uuid::new_v4(), NodeType::Leaf(dense_attribute(), vec![(conv, 0), (dense_attribute(), 1)])));
+
+    let tensor = Tensor::new(&[("input", constant(1.0, dim4!(1,16,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+
+    assert_eq!(vec![16, 4, 1], network.node().read().unwrap().mesh().topology());
+    assert_eq!(vec![4.5, 6.5, 6.5, 9.5], layer_values(&network, 1));
+    assert_eq!(vec![27.0], layer_values(&network, 2));
+}
+
+#[test]
+fn conv_input_layer() {
+    let conv = conv_attribute(CellType::Conv1d(Kernel::new_1d(3, 1, 0, (1, 1))));
+    let network = Network::new(Node::new(Uuid::new_v4(), NodeType::Leaf(conv, vec![(dense_attribute(), 2)])));
+
+    let tensor = Tensor::new(&[("input", Array::new_empty(dim4!(1,6,1,1)))]);
+    assert_eq!(Err("Input layer must be a dense cell."), network.node().read().unwrap().traverse(&tensor, true, Mode::Eval));
+}
+
+#[test]
+fn conv_kernel_too_large() {
+    let conv = conv_attribute(CellType::Conv1d(Kernel::new_1d(7, 1, 0, (1, 1))));
+    let network = Network::new(Node::new(Uuid::new_v4(), NodeType::Leaf(dense_attribute(), vec![(conv, 0)])));
+
+    let tensor = Tensor::new(&[("input", Array::new_empty(dim4!(1,6,1,1)))]);
+    assert_eq!(Err("Kernel is larger than the padded input."), network.node().read().unwrap().traverse(&tensor, true, Mode::Eval));
+}
@@ -0,0 +1,130 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use std::fs;
+use arrayfire::{constant, dim4};
+use uuid::Uuid;
+use crate::node::attribute::{Activation, Attribute, CellType, TensorDescriptor};
+use crate::node::processor::processor::Processor;
+use crate::node::mode::Mode;
+use crate::node::network::Network;
+use crate::node::node::{Node, NodeType};
+use crate::node::scope::Scope;
+use crate::node::tensor::Tensor;
+
+fn system_values(network: &Network) -> Vec<f64> {
+    let node = network.node();
+    let node = node.read().unwrap();
+    let values = node.mesh().tensor().hash_map.get("_SYSTEM_VALUES").unwrap().clone();
+    let mut host = vec![0.0; values.elements()];
+    values.host(&mut host);
+    host
+}
+
+fn explicit_network(input: Attribute, hidden: Attribute) -> Network {
+    let attribute = Attribute::new(Activation::None, CellType::Mlp, vec![], Scope::new(0,1));
+    Network::new(
+        Node::new(
+            Uuid::new_v4(),
+            NodeType::Leaf(
+                input,
+                vec![
+                    (hidden, 2),
+                    (attribute, 1),
+                ]
+            )))
+}
+
+#[test]
+fn explicit_values_feed_forward() {
+    let input = Attribute::new(
+        Activation::None,
+        CellType::Mlp,
+        vec![("_SYSTEM_WEIGHTS", TensorDescriptor::Values(vec![0.5, -0.25], vec![2,1])), ("_SYSTEM_BIASES", TensorDescriptor::Values(vec![0.25, 0.5], vec![2]))],
+        Scope::new(0,1));
+    let hidden = Attribute::new(
+        Activation::None,
+        CellType::Mlp,
+        vec![("_SYSTEM_WEIGHTS", TensorDescriptor::Values(vec![1.0, 2.0], vec![1,2])), ("_SYSTEM_BIASES", TensorDescriptor::Const(0.0))],
+        Scope::new(0,1));
+
+    let network = explicit_network(input, hidden);
+    let tensor = Tensor::new(&[("input", constant(1.0,dim4!(1,1,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+
+    assert_eq!(vec![1.0, 0.0, 0.75, 0.25, 1.25, 0.0], system_values(&network));
+}
+
+#[test]
+fn explicit_values_shape_mismatch() {
+    let input = Attribute::new(
+        Activation::None,
+        CellType::Mlp,
+        vec![("_SYSTEM_WEIGHTS", TensorDescriptor::Values(vec![0.5, -0.25, 1.0], vec![3,1])), ("_SYSTEM_BIASES", TensorDescriptor::Const(0.0))],
+        Scope::new(0,1));
+    let hidden = Attribute::new(
+        Activation::None,
+        CellType::Mlp,
+        vec![("_SYSTEM_WEIGHTS", TensorDescriptor::Const(1.0)), ("_SYSTEM_BIASES", TensorDescriptor::Const(0.0))],
+        Scope::new(0,1));
+
+    let network = explicit_network(input, hidden);
+    let tensor = Tensor::new(&[("input", constant(1.0,dim4!(1,1,1,1)))]);
+    assert_eq!(Err("Descriptor shape does not match the layer dimensions."), network.node().read().unwrap().traverse(&tensor, true, Mode::Eval));
+}
+
+#[test]
+fn explicit_values_several_blocks() {
+    let input = Attribute::new(
+        Activation::None,
+        CellType::Mlp,
+        vec![("_SYSTEM_WEIGHTS", TensorDescriptor::Values(vec![0.5, -0.25], vec![2,1])), ("_SYSTEM_BIASES", TensorDescriptor::Const(0.0))],
+        Scope::new(0,2));
+    let hidden = Attribute::new(
+        Activation::None,
+        CellType::Mlp,
+        vec![("_SYSTEM_WEIGHTS", TensorDescriptor::Const(1.0)), ("_SYSTEM_BIASES", TensorDescriptor::Const(0.0))],
+        Scope::new(0,1));
+
+    // The input layer connects to both following layers, so its explicit weights would be copied into each of their blocks.
+    let network = explicit_network(input, hidden);
+    let tensor = Tensor::new(&[("input", constant(1.0,dim4!(1,1,1,1)))]);
+    assert_eq!(Err("Explicit descriptor values require a Scope connecting the layer to a single other layer."), network.node().read().unwrap().traverse(&tensor, true, Mode::Eval));
+}
+
+#[test]
+fn file_values_feed_forward() {
+    let csv = std::env::temp_dir().join(format!("{}.csv", Uuid::new_v4()));
+    let npy_path = std::env::temp_dir().join(format!("{}.npy", Uuid::new_v4()));
+    fs::write(&csv, "0.5\n-0.25\n").unwrap();
+    let mut npy = b"\x93NUMPY\x01\x00".to_vec();
+    let header = "{'descr': '<f8', 'fortran_order': False, 'shape': (1, 2), }";
+    let header = format!("{}{}\n", header, " ".repeat(63 - (10 + header.len()) % 64));
+    npy.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    npy.extend_from_slice(header.as_bytes());
+    [1.0f64, 2.0].iter().for_each(|value| npy.extend_from_slice(&value.to_le_bytes()));
+    fs::write(&npy_path, npy).unwrap();
+
+    let input = Attribute::new(
+        Activation::None,
+        CellType::Mlp,
+        vec![("_SYSTEM_WEIGHTS", TensorDescriptor::File(csv.to_str().unwrap().to_string())), ("_SYSTEM_BIASES", TensorDescriptor::Values(vec![0.25, 0.5], vec![2,1]))],
+        Scope::new(0,1));
+    let hidden = Attribute::new(
+        Activation::None,
+        CellType::Mlp,
+        vec![("_SYSTEM_WEIGHTS", TensorDescriptor::File(npy_path.to_str().unwrap().to_string())), ("_SYSTEM_BIASES", TensorDescriptor::Const(0.0))],
+        Scope::new(0,1));
+
+    let network = explicit_network(input, hidden);
+    let tensor = Tensor::new(&[("input", constant(1.0,dim4!(1,1,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+    fs::remove_file(&csv).unwrap();
+    fs::remove_file(&npy_path).unwrap();
+
+    assert_eq!(vec![1.0, 0.0, 0.75, 0.25, 1.25, 0.0], system_values(&network));
+}
@@ -0,0 +1,54 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use uuid::Uuid;
+use crate::node::attribute::{Activation, Attribute, CellType};
+use crate::node::edges::{LinkType, NodeRange};
+use crate::node::network::Network;
+use crate::node::node::{Node, NodeType, VertexKind};
+use crate::node::scope::Scope;
+
+/// Leaf whose input layer also feeds its output layer.
+fn skip_leaf_node(uuid: Uuid) -> Node {
+    let skip = Attribute::new(Activation::Sigmoid, CellType::Mlp, vec![], Scope::new(0,2));
+    let attribute = Attribute::default();
+    Node::new(uuid, NodeType::Leaf(skip, vec![(attribute.clone(), 3), (attribute, 2)]))
+}
+
+/// Vertex with two skip leaves, the second reading the outputs of the first.
+fn skip_network(uuids: &[Uuid; 2]) -> Network {
+    let encoder = skip_leaf_node(uuids[0]).with_name("encoder");
+    let mut vertex = Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, Attribute::default(), encoder, Attribute::default()));
+    vertex.add_child_to_parent(skip_leaf_node(uuids[1]), None).unwrap();
+    let last = vertex.index_into_node(&[(1,0)]).unwrap();
+    last.lock().unwrap().mesh().edges().link(LinkType::InputsFromPeerOutputs((0,0)), NodeRange::Selective(vec![uuids[0].to_string()])).unwrap();
+    Network::new(vertex)
+}
+
+#[test]
+fn to_dot_links() {
+    let uuids = [Uuid::new_v4(), Uuid::new_v4()];
+    let dot = skip_network(&uuids).to_dot(false);
+
+    assert!(dot.starts_with("digraph anneml {"));
+    assert!(dot.contains(&format!("\"{}\" [label=\"encoder\\n{}\\n(0, 0)\\nleaf [0, 3, 2]\"];", uuids[0], uuids[0])));
+    assert!(dot.contains(&format!("\"{}\" -> \"{}\" [label=\"[{}]\", style=dotted];", uuids[0], uuids[1], uuids[0])));
+    assert!(dot.contains("style=dashed"));
+    assert!(dot.contains("\\nvertex Concatenate\", shape=ellipse]"));
+}
+
+#[test]
+fn to_dot_layers() {
+    let uuids = [Uuid::new_v4(), Uuid::new_v4()];
+    let dot = skip_network(&uuids).to_dot(true);
+
+    assert!(dot.contains(&format!("subgraph \"cluster_{}\"", uuids[1])));
+    assert!(dot.contains(&format!("\"{}:1\" [label=\"1: 3 Mlp Sigmoid\"];", uuids[1])));
+    assert!(dot.contains(&format!("\"{}:0\" -> \"{}:1\";", uuids[1], uuids[1])));
+    assert!(dot.contains(&format!("\"{}:0\" -> \"{}:2\" [style=dashed, label=\"skip\"];", uuids[1], uuids[1])));
+    assert!(dot.contains(&format!("\"{}:2\" -> \"{}:0\" [label=\"[{}]\", style=dotted];", uuids[0], uuids[1], uuids[0])));
+}
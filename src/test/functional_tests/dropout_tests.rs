@@ -0,0 +1,71 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use arrayfire::{constant, dim4};
+use uuid::Uuid;
+use crate::node::attribute::{Activation, Attribute, CellType, TensorDescriptor};
+use crate::node::processor::processor::Processor;
+use crate::node::mode::Mode;
+use crate::node::network::Network;
+use crate::node::node::{Node, NodeType};
+use crate::node::scope::Scope;
+use crate::node::tensor::Tensor;
+use crate::test::functional_tests::helpers::layer_values;
+
+const WIDTH: usize = 64;
+
+fn dropout_network(uuid: Uuid, rate: f64) -> Network {
+    let attribute = Attribute::new(
+        Activation::None,
+        CellType::Mlp,
+        vec![("_SYSTEM_WEIGHTS", TensorDescriptor::Const(1.0)), ("_SYSTEM_BIASES", TensorDescriptor::Const(0.0))],
+        Scope::new(0,1));
+    Network::with_seed(Node::new(uuid, NodeType::Leaf(attribute.clone().with_dropout(rate), vec![(attribute, 1)])), 7)
+}
+
+#[test]
+fn dropout_disabled_in_eval() {
+    let network = dropout_network(Uuid::new_v4(), 0.5);
+    let tensor = Tensor::new(&[("input", constant(1.0, dim4!(1,WIDTH as u64,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+
+    assert_eq!(vec![1.0; WIDTH], layer_values(&network, 0));
+}
+
+#[test]
+fn dropout_scales_kept_values() {
+    let network = dropout_network(Uuid::new_v4(), 0.5);
+    let tensor = Tensor::new(&[("input", constant(1.0, dim4!(1,WIDTH as u64,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Train).unwrap();
+
+    let values = layer_values(&network, 0);
+    assert!(values.iter().all(|value| *value == 0.0 || *value == 2.0));
+    assert!(values.contains(&0.0));
+    assert!(values.contains(&2.0));
+}
+
+#[test]
+fn dropout_reproducible() {
+    let uuid = Uuid::new_v4();
+    let tensor = Tensor::new(&[("input", constant(1.0, dim4!(1,WIDTH as u64,1,1)))]);
+    let masks = [dropout_network(uuid, 0.5), dropout_network(uuid, 0.5)].map(|network| {
+        network.node().read().unwrap().traverse(&tensor, true, Mode::Train).unwrap();
+        let first = layer_values(&network, 0);
+        network.node().read().unwrap().traverse(&tensor, false, Mode::Train).unwrap();
+        (first, layer_values(&network, 0))
+    });
+
+    assert_eq!(masks[0], masks[1]);
+    assert_ne!(masks[0].0, masks[0].1);
+}
+
+#[test]
+fn dropout_rate_out_of_range() {
+    let network = dropout_network(Uuid::new_v4(), 1.0);
+    let tensor = Tensor::new(&[("input", constant(1.0, dim4!(1,WIDTH as u64,1,1)))]);
+    assert_eq!(Err("Dropout rate must be within [0, 1)."), network.node().read().unwrap().traverse(&tensor, true, Mode::Train));
+}
@@ -0,0 +1,71 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use arrayfire::{Array, dim4};
+use uuid::Uuid;
+use crate::node::attribute::{Activation, Attribute, CellType, TensorDescriptor};
+use crate::node::processor::processor::Processor;
+use crate::node::edges::{KeyMap, NodeRange};
+use crate::node::mode::Mode;
+use crate::node::network::Network;
+use crate::node::node::{Node, NodeType, VertexKind};
+use crate::node::scope::Scope;
+use crate::node::tensor::Tensor;
+use crate::test::functional_tests::helpers::{dense_attribute, host, layer_values};
+
+fn embedding_node() -> Node {
+    let embedding = Attribute::new(
+        Activation::None,
+        CellType::Embedding("ids".to_string(), 3, 2),
+        vec![("_SYSTEM_EMBEDDINGS", TensorDescriptor::Values(vec![0.0, 1.0, 2.0, 10.0, 11.0, 12.0], vec![3,2])),
+             ("_SYSTEM_WEIGHTS", TensorDescriptor::Const(1.0)), ("_SYSTEM_BIASES", TensorDescriptor::Const(0.0))],
+        Scope::new(0,1));
+    Node::new(Uuid::new_v4(), NodeType::Leaf(dense_attribute(), vec![(embedding, 0), (dense_attribute(), 1)]))
+}
+
+fn embedding_network() -> Network {
+    Network::new(embedding_node())
+}
+
+#[test]
+fn embedding_lookup() {
+    let network = embedding_network();
+    let tensor = Tensor::new(&[("ids", Array::new(&[2.0, 0.0], dim4!(1,2,1,1))), ("scalar", Array::new(&[1.0], dim4!(1,1,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+
+    assert_eq!(vec![3, 4, 1], network.node().read().unwrap().mesh().topology());
+    assert_eq!(vec![2.0, 12.0, 0.0, 10.0], layer_values(&network, 1));
+    assert_eq!(vec![24.0], layer_values(&network, 2));
+}
+
+#[test]
+fn embedding_reads_aliased_key() {
+    let leaf = embedding_node();
+    leaf.link_input_from_parent(NodeRange::Mapped(vec![KeyMap::new("tokens").with_alias("ids"), KeyMap::new("scalar")])).unwrap();
+    let vertex = Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, Attribute::default(), leaf, Attribute::default()));
+    let tensor = Tensor::new(&[("tokens", Array::new(&[2.0, 0.0], dim4!(1,2,1,1))), ("scalar", Array::new(&[1.0], dim4!(1,1,1,1)))]);
+    vertex.traverse(&tensor, true, Mode::Eval).unwrap();
+
+    let leaf = vertex.index_into_node(&[(0,0)]).unwrap();
+    let leaf = leaf.lock().unwrap();
+    assert_eq!(vec![3, 4, 1], leaf.mesh().topology());
+    assert_eq!(vec![24.0], host(leaf.mesh().tensor().hash_map.get(&leaf.uuid().to_string()).unwrap()));
+}
+
+#[test]
+fn embedding_missing_key() {
+    let network = embedding_network();
+    let tensor = Tensor::new(&[("scalar", Array::new(&[1.0], dim4!(1,1,1,1)))]);
+    assert_eq!(Err("Embedding key is not an input of the Node."), network.node().read().unwrap().traverse(&tensor, true, Mode::Eval));
+}
+
+#[test]
+fn embedding_index_out_of_vocabulary() {
+    let network = embedding_network();
+    let tensor = Tensor::new(&[("ids", Array::new(&[3.0], dim4!(1,1,1,1)))]);
+    assert_eq!(Err("Embedding index is outside of the vocabulary."), network.node().read().unwrap().traverse(&tensor, true, Mode::Eval));
+}
@@ -11,10 +11,12 @@ use crate::node::attribute::{Activation, Attribute, CellType, TensorDescriptor};
 use crate::node::edges::LinkType::InputsFromParent;
 use crate::node::edges::NodeRange;
 use crate::node::processor::processor::Processor;
+use crate::node::mode::Mode;
 use crate::node::network::Network;
-use crate::node::node::{Node, NodeType};
+use crate::node::node::{Node, NodeType, VertexKind};
 use crate::node::scope::Scope;
 use crate::node::tensor::Tensor;
+use crate::test::functional_tests::helpers::host;
 
 #[test]
 fn feed_forward_1x1_122_no_activation() {
@@ -36,7 +38,7 @@ fn feed_forward_1x1_122_no_activation() {
         )));
 
     let tensor = Tensor::new(&[("input", constant(1.0,Dim4::new(&[1,1,1,1])))]);
-    network.node().read().unwrap().traverse(&tensor, true);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
 
     //not working because you are testing against the whole value array, not just the output.
     let expected : Vec<u8> = vec![2, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0,
@@ -64,7 +66,7 @@ fn feed_forward_1x1_21_no_activation(){
             )));
 
     let tensor = Tensor::new(&[("input", constant(1.0,Dim4::new(&[1,2,1,1])))]);
-    network.node().read().unwrap().traverse(&tensor, true);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
 
     let expected : Vec<u8> = vec![2, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0,
                                   1, 0, 0, 0, 0, 0, 0, 0, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0,
@@ -72,6 +74,27 @@ fn feed_forward_1x1_21_no_activation(){
     assert_eq!(&expected, &bincode::serialize(&network.node().read().unwrap().mesh().tensor().hash_map.get("_SYSTEM_VALUES").unwrap()).unwrap());
 }
 
+#[test]
+fn feed_forward_output_under_uuid(){
+    let attribute = Attribute::new(
+        Activation::None,
+        CellType::Mlp,
+        vec![("_SYSTEM_WEIGHTS", TensorDescriptor::Const(0.3)), ("_SYSTEM_BIASES", TensorDescriptor::Const(0.5))],
+        Scope::new(0,1));
+
+    let uuid = Uuid::new_v4();
+    let network = Network::new(Node::new(uuid, NodeType::Leaf(attribute.clone(), vec![(attribute.clone(), 1)])));
+
+    let tensor = Tensor::new(&[("input", constant(1.0,Dim4::new(&[1,2,1,1])))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+
+    let node = network.node();
+    let node = node.read().unwrap();
+    assert!((host(&node.output())[0] - 1.1).abs() < 1e-12);
+    assert!((host(node.mesh().tensor().hash_map.get(&uuid.to_string()).unwrap())[0] - 1.1).abs() < 1e-12);
+    assert!((host(node.mesh().layers()[1].lock().unwrap().tensor.hash_map.get(&uuid.to_string()).unwrap())[0] - 1.1).abs() < 1e-12);
+}
+
 #[test]
 fn feed_forward_1x1_21_sigmoid(){
     let attribute = Attribute::new(
@@ -91,7 +114,7 @@ fn feed_forward_1x1_21_sigmoid(){
             )));
 
     let tensor = Tensor::new(&[("input", constant(1.0,Dim4::new(&[1,2,1,1])))]);
-    network.node().read().unwrap().traverse(&tensor, true);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
 
     let expected : Vec<u8> = vec![2, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 1, 0,
                                   0, 0, 0, 0, 0, 0, 4, 0, 0, 0, 0, 0, 0, 0, 189, 162, 213, 245, 212, 100, 231, 63, 189, 162,
@@ -112,6 +135,7 @@ fn feed_forward_1x2_122_21_no_activation(){
         Node::new(
             Uuid::new_v4(),
             NodeType::Vertex(
+                VertexKind::Aggregate,
                 attribute.clone(),
                 Node::new(
                     Uuid::new_v4(),
@@ -136,7 +160,7 @@ fn feed_forward_1x2_122_21_no_activation(){
                 ]
             )),
         Some(0)
-    );
+    ).unwrap();
 
     //Only pass some inputs to children
     let input0 = ("input0",constant(1.0,Dim4::new(&[1,1,1,1])));
@@ -146,7 +170,7 @@ fn feed_forward_1x2_122_21_no_activation(){
     assert_eq!(network.node().read().unwrap().index_into_node(&[(0,1)]).unwrap().lock().unwrap().mesh().edges().link(InputsFromParent, NodeRange::Selective(vec!["input1".to_string()])), Ok(()));
 
     let tensor = Tensor::new(&[input0, input1]);
-    network.node().read().unwrap().traverse(&tensor, true);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
 
     let expected0 : Vec<u8> = vec![2, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 6,
                                    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0, 0, 0, 0, 0, 0, 154, 153, 153, 153, 153, 153, 233, 63,
@@ -0,0 +1,126 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use arrayfire::{Array, constant, dim4, sum_all};
+use uuid::Uuid;
+use crate::node::attribute::Attribute;
+use crate::node::edges::{LinkType, NodeRange};
+use crate::node::function::{Function, register_function};
+use crate::node::processor::processor::Processor;
+use crate::node::mode::Mode;
+use crate::node::network::Network;
+use crate::node::node::{Node, NodeType, VertexKind};
+use crate::node::tensor::Tensor;
+use crate::test::functional_tests::helpers::{dense_attribute, host};
+
+/// Sums every value of every input, scaled by the configured factor.
+struct Total {
+    factor: f64,
+}
+
+impl Function for Total {
+    fn name(&self) -> &str { "total" }
+
+    fn configuration(&self) -> String { self.factor.to_string() }
+
+    fn call(&self, inputs: &Tensor) -> Result<Tensor, &'static str> {
+        let total = inputs.iter().map(|(_, value)| sum_all(value).0).sum::<f64>();
+        Ok(Tensor::new(&[("total", constant(total * self.factor, dim4!(1,1,1,1)))]))
+    }
+}
+
+/// Passes the input on under the name of its sign.
+struct Sign;
+
+impl Function for Sign {
+    fn name(&self) -> &str { "sign" }
+
+    fn call(&self, inputs: &Tensor) -> Result<Tensor, &'static str> {
+        let input = inputs.get("input").ok_or("Missing input.")?;
+        let key = if sum_all(input).0 < 0.0 { "negative" } else { "positive" };
+        Ok(Tensor::new(&[(key, input.clone())]))
+    }
+}
+
+fn register() {
+    register_function("total", |configuration| {
+        let factor = configuration.parse::<f64>().map_err(|_| "Invalid total configuration.")?;
+        Ok(Box::new(Total { factor }))
+    });
+    register_function("sign", |_| Ok(Box::new(Sign)));
+}
+
+fn leaf_node() -> Node {
+    Node::new(Uuid::new_v4(), NodeType::Leaf(dense_attribute(), vec![(dense_attribute(), 2)]))
+}
+
+fn total(network: &Network) -> f64 {
+    let node = network.node();
+    let node = node.read().unwrap();
+    let mut host = [0.0];
+    node.mesh().tensor().hash_map.get("total").unwrap().host(&mut host);
+    host[0]
+}
+
+#[test]
+fn function_node_parent_inputs() {
+    register();
+    let function = Node::new(Uuid::new_v4(), NodeType::function(&Total { factor: 2.0 }));
+    let network = Network::new(Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, Attribute::default(), function, Attribute::default())));
+
+    let tensor = Tensor::new(&[("a", Array::new(&[1.0, 2.0], dim4!(1,2,1,1))), ("b", constant(0.5, dim4!(1,1,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+
+    assert_eq!(7.0, total(&network));
+}
+
+#[test]
+fn function_node_between_children() {
+    register();
+    let network = Network::new(Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, Attribute::default(), leaf_node(), Attribute::default())));
+    network.node().write().unwrap().add_child_to_parent(Node::new(Uuid::new_v4(), NodeType::function(&Total { factor: 1.0 })), None).unwrap();
+    network.node().read().unwrap().index_into_node(&[(1,0)]).unwrap().lock().unwrap().mesh().edges()
+        .link(LinkType::InputsFromPeerOutputs((0,0)), NodeRange::All).unwrap();
+
+    let tensor = Tensor::new(&[("input", constant(1.0, dim4!(1,1,1,1)))]);
+    // The leaf is traversed before the function, so its output reaches the function on the same pass.
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+    assert_eq!(3.0, total(&network));
+
+    network.node().read().unwrap().traverse(&tensor, false, Mode::Eval).unwrap();
+    assert_eq!(3.0, total(&network));
+}
+
+#[test]
+fn function_node_without_children() {
+    let mut function = Node::new(Uuid::new_v4(), NodeType::function(&Total { factor: 1.0 }));
+
+    assert!(function.is_function_node());
+    assert_eq!(Err("Unable to add child to function node."), function.add_child_to_parent(leaf_node(), None));
+    assert!(function.index_into_node(&[(0,0)]).is_err());
+}
+
+#[test]
+fn function_node_replaces_outputs() {
+    register();
+    let network = Network::new(Node::new(Uuid::new_v4(), NodeType::function(&Sign)));
+
+    network.node().read().unwrap().traverse(&Tensor::new(&[("input", constant(1.0, dim4!(1,1,1,1)))]), true, Mode::Eval).unwrap();
+    network.node().read().unwrap().traverse(&Tensor::new(&[("input", constant(-1.0, dim4!(1,1,1,1)))]), false, Mode::Eval).unwrap();
+
+    let node = network.node();
+    let node = node.read().unwrap();
+    assert_eq!(vec![-1.0], host(&node.output()));
+    assert!(!node.mesh().tensor().hash_map.contains_key("positive"));
+}
+
+#[test]
+fn function_node_unregistered() {
+    let network = Network::new(Node::new(Uuid::new_v4(), NodeType::Function("unregistered".to_string(), String::new())));
+    let tensor = Tensor::new(&[("input", constant(1.0, dim4!(1,1,1,1)))]);
+    assert_eq!(Err("Function is not registered."), network.node().read().unwrap().traverse(&tensor, true, Mode::Eval));
+}
@@ -0,0 +1,62 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use arrayfire::{constant, dim4};
+use uuid::Uuid;
+use crate::node::attribute::Attribute;
+use crate::node::processor::processor::Processor;
+use crate::node::mode::Mode;
+use crate::node::network::Network;
+use crate::node::node::{Node, NodeType, Selection, VertexKind};
+use crate::node::tensor::Tensor;
+use crate::test::functional_tests::helpers::{host, weighted_leaf_node};
+
+fn gated_network(uuid: Uuid, selection: Selection) -> Network {
+    let network = Network::new(Node::new(uuid, NodeType::Vertex(VertexKind::Gated(selection), Attribute::default(), weighted_leaf_node(&[0.0, 0.0, 2f64.ln()]), Attribute::default())));
+    [[1.0, 2.0], [3.0, 0.0], [2.0, 4.0]].iter().for_each(|outputs| network.node().write().unwrap().add_child_to_parent(weighted_leaf_node(outputs), None).unwrap());
+    network
+}
+
+fn gated_output(uuid: Uuid, network: &Network, input: f64, build: bool) -> Vec<f64> {
+    let tensor = Tensor::new(&[("input", constant(input, dim4!(1,1,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, build, Mode::Eval).unwrap();
+    host(network.node().read().unwrap().mesh().tensor().hash_map.get(&uuid.to_string()).unwrap())
+}
+
+#[test]
+fn gated_dense_mixture() {
+    let uuid = Uuid::new_v4();
+    let network = gated_network(uuid, Selection::All);
+
+    let output = gated_output(uuid, &network, 1.0, true);
+    [2.0, 2.5].iter().zip(output.iter()).for_each(|(expected, value)| assert!((expected - value).abs() < 1e-12));
+}
+
+#[test]
+fn gated_top_k_skips_experts() {
+    let uuid = Uuid::new_v4();
+    let network = gated_network(uuid, Selection::TopK(1));
+
+    let output = gated_output(uuid, &network, 1.0, true);
+    [2.0, 4.0].iter().zip(output.iter()).for_each(|(expected, value)| assert!((expected - value).abs() < 1e-12));
+
+    let output = gated_output(uuid, &network, 2.0, false);
+    [4.0, 8.0].iter().zip(output.iter()).for_each(|(expected, value)| assert!((expected - value).abs() < 1e-12));
+
+    // The unselected expert kept the output of the building pass.
+    let expert = network.node().read().unwrap().index_into_node(&[(1,0)]).unwrap();
+    let expert = expert.lock().unwrap();
+    assert_eq!(vec![1.0, 2.0], host(&expert.output()));
+}
+
+#[test]
+fn gated_gate_mismatch() {
+    let network = Network::new(Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Gated(Selection::All), Attribute::default(), weighted_leaf_node(&[0.0, 1.0]), Attribute::default())));
+    network.node().write().unwrap().add_child_to_parent(weighted_leaf_node(&[1.0]), None).unwrap();
+    let tensor = Tensor::new(&[("input", constant(1.0, dim4!(1,1,1,1)))]);
+    assert_eq!(Err("Gate outputs do not match the number of experts."), network.node().read().unwrap().traverse(&tensor, true, Mode::Eval));
+}
@@ -0,0 +1,78 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use std::sync::{Arc, Mutex};
+use arrayfire::Array;
+use uuid::Uuid;
+use crate::node::attribute::{Activation, Attribute, CellType, TensorDescriptor};
+use crate::node::edges::{LinkType, NodeRange};
+use crate::node::network::Network;
+use crate::node::node::{Node, NodeType, VertexKind};
+use crate::node::scope::Scope;
+
+/// Copies the values of an array to the host.
+pub(crate) fn host(array: &Array<f64>) -> Vec<f64> {
+    let mut host = vec![0.0; array.elements()];
+    array.host(&mut host);
+    host
+}
+
+/// Dense Attribute without activation, whose weights are all 1 and biases 0.
+pub(crate) fn dense_attribute() -> Attribute {
+    Attribute::new(
+        Activation::None,
+        CellType::Mlp,
+        vec![("_SYSTEM_WEIGHTS", TensorDescriptor::Const(1.0)), ("_SYSTEM_BIASES", TensorDescriptor::Const(0.0))],
+        Scope::new(0,1))
+}
+
+/// Values of a layer of the Network's root leaf, after its last pass.
+pub(crate) fn layer_values(network: &Network, layer: usize) -> Vec<f64> {
+    let node = network.node();
+    let node = node.read().unwrap();
+    let topology = node.mesh().topology();
+    let max = *topology.iter().max().unwrap();
+    host(node.mesh().tensor().hash_map.get("_SYSTEM_VALUES").unwrap())[layer * max..layer * max + topology[layer]].to_vec()
+}
+
+/// Leaf with a single hidden layer of `count` values, all Attributes being the default.
+pub(crate) fn leaf_node(uuid: Uuid, count: u64) -> Node {
+    let attribute = Attribute::default();
+    Node::new(uuid, NodeType::Leaf(attribute.clone(), vec![(attribute, count)]))
+}
+
+/// Leaf turning a single input into `outputs`, multiplying it by each of them.
+pub(crate) fn weighted_leaf_node(outputs: &[f64]) -> Node {
+    weighted_leaf(Uuid::new_v4(), outputs)
+}
+
+/// Leaf with `uuid` turning a single input into `outputs`, multiplying it by each of them.
+pub(crate) fn weighted_leaf(uuid: Uuid, outputs: &[f64]) -> Node {
+    let input = Attribute::new(
+        Activation::None,
+        CellType::Mlp,
+        vec![("_SYSTEM_WEIGHTS", TensorDescriptor::Values(outputs.to_vec(), vec![outputs.len() as u64, 1])), ("_SYSTEM_BIASES", TensorDescriptor::Const(0.0))],
+        Scope::new(0,1));
+    let output = Attribute::new(Activation::None, CellType::Mlp, vec![], Scope::new(0,0));
+    Node::new(uuid, NodeType::Leaf(input, vec![(output, outputs.len() as u64)]))
+}
+
+/// Network over two default leaves, the second reading the outputs of the first.
+pub(crate) fn linked_network(uuids: &[Uuid; 2]) -> Network {
+    let mut vertex = Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, Attribute::default(), leaf_node(uuids[0], 2), Attribute::default()));
+    vertex.add_child_to_parent(leaf_node(uuids[1], 2), None).unwrap();
+    let last = vertex.index_into_node(&[(1,0)]).unwrap();
+    last.lock().unwrap().mesh().edges().link(LinkType::InputsFromPeerOutputs((0,0)), NodeRange::All).unwrap();
+    Network::new(vertex)
+}
+
+/// Positions of the peers a child reads the outputs of.
+pub(crate) fn peer_links(child: &Arc<Mutex<Node>>) -> Vec<(usize, usize)> {
+    let child = child.lock().unwrap();
+    let links = child.mesh().edges().input_from_peer_output.iter().map(|(position, _)| *position).collect();
+    links
+}
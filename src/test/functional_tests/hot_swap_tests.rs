@@ -0,0 +1,94 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use arrayfire::{constant, dim4};
+use uuid::Uuid;
+use crate::node::attribute::{Activation, Attribute, CellType, TensorDescriptor};
+use crate::node::processor::processor::Processor;
+use crate::node::mode::Mode;
+use crate::node::network::Network;
+use crate::node::node::{Node, NodeType, VertexKind};
+use crate::node::scope::Scope;
+use crate::node::tensor::Tensor;
+use crate::test::functional_tests::helpers::{host, leaf_node, linked_network, peer_links, weighted_leaf};
+
+/// Network over a single leaf multiplying its input by 2, after a first pass building it.
+fn built_network(uuid: Uuid) -> Network {
+    let network = Network::new(Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, Attribute::default(), weighted_leaf(uuid, &[2.0]), Attribute::default())));
+    network.node().read().unwrap().traverse(&input(), true, Mode::Eval).unwrap();
+    network
+}
+
+fn input() -> Tensor {
+    Tensor::new(&[("input", constant(1.0, dim4!(1,1,1,1)))])
+}
+
+#[test]
+fn hot_swap_keeps_edges() {
+    let uuids = [Uuid::new_v4(), Uuid::new_v4()];
+    let network = linked_network(&uuids);
+
+    let previous = network.hot_swap(uuids[1], leaf_node(uuids[1], 2)).unwrap();
+    assert_eq!(uuids[1], previous.uuid());
+    assert!(previous.mesh().edges().input_from_peer_output.is_empty());
+    assert_eq!(vec![(0,0)], peer_links(&network.find_by_uuid(uuids[1]).unwrap()));
+}
+
+#[test]
+fn hot_swap_rollback() {
+    let uuids = [Uuid::new_v4(), Uuid::new_v4()];
+    let network = linked_network(&uuids);
+
+    let previous = network.hot_swap(uuids[0], leaf_node(uuids[0], 2)).unwrap();
+    let replacement = network.hot_swap(uuids[0], previous).unwrap();
+    assert_eq!(uuids[0], replacement.uuid());
+    assert_eq!(uuids[0], network.find_by_uuid(uuids[0]).unwrap().lock().unwrap().uuid());
+}
+
+#[test]
+fn hot_swap_incompatible() {
+    let uuids = [Uuid::new_v4(), Uuid::new_v4()];
+    let network = linked_network(&uuids);
+
+    // Leaf outputs are keyed by UUID, so a different leaf does not provide the outputs.
+    assert!(network.hot_swap(uuids[0], leaf_node(Uuid::new_v4(), 2)).is_err());
+    assert!(network.hot_swap(uuids[0], leaf_node(uuids[0], 3)).is_err());
+    assert!(network.hot_swap(Uuid::new_v4(), leaf_node(uuids[0], 2)).is_err());
+    assert_eq!(vec![(0,0)], peer_links(&network.find_by_uuid(uuids[1]).unwrap()));
+}
+
+#[test]
+fn hot_swap_builds_replacement() {
+    let uuid = Uuid::new_v4();
+    let network = built_network(uuid);
+
+    // The network is not built again, so the replacement has to be built by the swap.
+    network.hot_swap(uuid, weighted_leaf(uuid, &[3.0])).unwrap();
+    network.node().read().unwrap().traverse(&input(), false, Mode::Eval).unwrap();
+    let leaf = network.find_by_uuid(uuid).unwrap();
+    let leaf = leaf.lock().unwrap();
+    assert_eq!(vec![3.0], host(leaf.mesh().tensor().get(&uuid.to_string()).unwrap()));
+}
+
+#[test]
+fn hot_swap_build_failure() {
+    let uuid = Uuid::new_v4();
+    let network = built_network(uuid);
+
+    // The replacement's weights do not match its single output, so it can not be built.
+    let hidden = Attribute::new(
+        Activation::None,
+        CellType::Mlp,
+        vec![("_SYSTEM_WEIGHTS", TensorDescriptor::Values(vec![3.0, 4.0], vec![2, 1])), ("_SYSTEM_BIASES", TensorDescriptor::Const(0.0))],
+        Scope::new(0,1));
+    let replacement = Node::new(uuid, NodeType::Leaf(hidden, vec![(Attribute::default(), 1)]));
+    assert_eq!(Err("Descriptor shape does not match the layer dimensions."), network.hot_swap(uuid, replacement).map(|_| ()));
+    network.node().read().unwrap().traverse(&input(), false, Mode::Eval).unwrap();
+    let leaf = network.find_by_uuid(uuid).unwrap();
+    let leaf = leaf.lock().unwrap();
+    assert_eq!(vec![2.0], host(leaf.mesh().tensor().get(&uuid.to_string()).unwrap()));
+}
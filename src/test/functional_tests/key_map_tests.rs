@@ -0,0 +1,83 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use arrayfire::{Array, dim4};
+use uuid::Uuid;
+use crate::node::attribute::Attribute;
+use crate::node::edges::{KeyMap, LinkError, NodeRange};
+use crate::node::processor::processor::Processor;
+use crate::node::mode::Mode;
+use crate::node::node::{Node, NodeType, VertexKind};
+use crate::node::tensor::Tensor;
+use crate::test::functional_tests::helpers::host;
+
+fn row(values: &[f64]) -> Array<f64> {
+    Array::new(values, dim4!(1, values.len() as u64, 1, 1))
+}
+
+fn inputs() -> Tensor {
+    Tensor::new(&[("features", row(&[0.0, 1.0, 2.0, 3.0])), ("embedding_a", row(&[4.0])), ("embedding_b", row(&[5.0])), ("_SYSTEM_VALUES", row(&[6.0]))])
+}
+
+#[test]
+fn key_map_columns_and_alias() {
+    let node_range = NodeRange::Mapped(vec![KeyMap::new("features").with_columns(1..3).with_alias("score"), KeyMap::new("features").with_columns(3..9)]);
+    let mut selected = node_range.select(&inputs());
+    selected.sort_by(|a, b| a.0.cmp(&b.0));
+
+    assert_eq!(vec!["features", "score"], selected.iter().map(|(key, _)| key.as_str()).collect::<Vec<&str>>());
+    assert_eq!(vec![3.0], host(&selected[0].1));
+    assert_eq!(vec![1.0, 2.0], host(&selected[1].1));
+    assert!(NodeRange::Mapped(vec![KeyMap::new("features").with_columns(4..6)]).select(&inputs()).is_empty());
+}
+
+#[test]
+fn key_map_patterns() {
+    let keys = |pattern: &str| {
+        let mut keys = NodeRange::Mapped(vec![KeyMap::new(pattern)]).select(&inputs()).into_iter().map(|(key, _)| key).collect::<Vec<String>>();
+        keys.sort();
+        keys
+    };
+
+    assert_eq!(vec!["embedding_a", "embedding_b"], keys("embedding_*"));
+    assert_eq!(vec!["embedding_a", "embedding_b"], keys("embedding_?"));
+    assert_eq!(vec!["embedding_b"], keys("*_b"));
+    assert_eq!(3, keys("*").len());
+    assert!(keys("feature").is_empty());
+}
+
+#[test]
+fn key_map_parent_inputs() {
+    let attribute = Attribute::default();
+    let leaf = Node::new(Uuid::new_v4(), NodeType::Leaf(attribute.clone(), vec![(attribute.clone(), 2)]));
+    leaf.link_input_from_parent(NodeRange::Mapped(vec![KeyMap::new("features").with_columns(1..3).with_alias("score")])).unwrap();
+    let vertex = Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, attribute.clone(), leaf, attribute));
+
+    vertex.traverse(&inputs(), true, Mode::Eval).unwrap();
+    let leaf = vertex.index_into_node(&[(0,0)]).unwrap();
+    let leaf = leaf.lock().unwrap();
+    let mesh = leaf.mesh();
+    let input = mesh.layers()[0].lock().unwrap();
+    assert_eq!(vec!["score"], input.tensor.iter().map(|(key, _)| key.as_str()).filter(|key| !key.contains("_SYSTEM")).collect::<Vec<&str>>());
+    assert_eq!(vec![1.0, 2.0], host(input.tensor.get("score").unwrap()));
+}
+
+#[test]
+fn key_map_validation() {
+    let uuid = Uuid::new_v4();
+    let attribute = Attribute::default();
+    let leaf = Node::new(uuid, NodeType::Leaf(attribute.clone(), vec![(attribute, 2)]));
+    let mapped = |key_maps: Vec<KeyMap>| NodeRange::Mapped(key_maps);
+
+    assert_eq!(Err(LinkError::EmptyColumns("features".to_string())), leaf.link_input_from_parent(mapped(vec![KeyMap::new("features").with_columns(2..2)])));
+    assert_eq!(Err(LinkError::ReservedKey("_SYSTEM_VALUES".to_string())), leaf.link_input_from_parent(mapped(vec![KeyMap::new("x").with_alias("_SYSTEM_VALUES")])));
+    assert_eq!(Err(LinkError::DuplicateKey("score".to_string())), leaf.link_input_from_parent(mapped(vec![KeyMap::new("a").with_alias("score"), KeyMap::new("b").with_alias("score")])));
+    assert_eq!(Err(LinkError::UnknownKey("other*".to_string())), leaf.link_output_to_parent(mapped(vec![KeyMap::new("other*")])));
+    assert_eq!(Err(LinkError::UnknownKey(uuid.to_string()[..8].to_string())), leaf.link_output_to_parent(mapped(vec![KeyMap::new(&uuid.to_string()[..8])])));
+    assert_eq!(Ok(()), leaf.link_output_to_parent(mapped(vec![KeyMap::new(&format!("{}*", &uuid.to_string()[..8])).with_alias("score")])));
+    assert_eq!("features[1..3] -> score", KeyMap::new("features").with_columns(1..3).with_alias("score").to_string());
+}
@@ -5,17 +5,19 @@
  * (c) Copyright by Christian Potts
  */
 
-use arrayfire::{Array, dim4};
+use arrayfire::{Array, constant, dim4};
 use uuid::Uuid;
 use crate::node::attribute::{Activation, Attribute, CellType, TensorDescriptor};
 use crate::node::edges::{LinkType, NodeRange};
 use crate::node::edges::LinkType::{InputsFromParent, OutputsToParent};
 use crate::node::layer::Layer;
 use crate::node::processor::processor::Processor;
+use crate::node::mode::Mode;
 use crate::node::network::Network;
-use crate::node::node::{Node, NodeType};
+use crate::node::node::{Node, NodeType, VertexKind};
 use crate::node::scope::Scope;
 use crate::node::tensor::Tensor;
+use crate::test::functional_tests::helpers::{host, weighted_leaf_node};
 
 
 fn tensor_sum(list: &Layer) -> usize {
@@ -35,6 +37,7 @@ fn create_links_r_w2m_all(){
         Node::new(
         Uuid::new_v4(),
         NodeType::Vertex(
+        VertexKind::Aggregate,
         attribute.clone(),
         Node::new(
             Uuid::new_v4(),
@@ -71,7 +74,7 @@ fn create_links_r_w2m_all(){
     assert_eq!(parent.node().read().unwrap().index_into_node(&[(0,1)]).unwrap().lock().unwrap().mesh().edges().link(InputsFromParent, NodeRange::All), Ok(()));
 
     let inputs = Tensor::new(&[("input", Array::new_empty(dim4!(1,3,1,1)))]);
-    parent.node().read().unwrap().traverse(&inputs, true);
+    parent.node().read().unwrap().traverse(&inputs, true, Mode::Eval).unwrap();
 
     //Root
     assert_eq!(tensor_sum(&parent.node().read().unwrap().mesh().layers()[0].lock().unwrap()), 3);
@@ -99,6 +102,7 @@ fn create_links_r_w2m_selective(){
         Node::new(
         Uuid::new_v4(),
         NodeType::Vertex(
+            VertexKind::Aggregate,
             attribute.clone(),
             Node::new(
                 Uuid::new_v4(),
@@ -134,7 +138,7 @@ fn create_links_r_w2m_selective(){
     let input0 = ("input0",Array::new_empty(dim4!(1,2,1,1)));
     let input1 = ("input1",Array::new_empty(dim4!(1,1,1,1)));
     let inputs = Tensor::new(&[input0, input1]);
-    parent.node().read().unwrap().traverse(&inputs, true);
+    parent.node().read().unwrap().traverse(&inputs, true, Mode::Eval).unwrap();
 
     //Root
     assert_eq!(tensor_sum(&parent.node().read().unwrap().mesh().layers()[0].lock().unwrap()), 3);
@@ -162,6 +166,7 @@ fn create_links_r_w2m_wm1out0(){
         Node::new(
         Uuid::new_v4(),
         NodeType::Vertex(
+            VertexKind::Aggregate,
             attribute.clone(),
             Node::new(
                 Uuid::new_v4(),
@@ -197,7 +202,7 @@ fn create_links_r_w2m_wm1out0(){
 
     let input0 = ("input0",Array::new_empty(dim4!(1,3,1,1)));
     let inputs = Tensor::new(&[input0]);
-    parent.node().read().unwrap().traverse(&inputs, true);
+    parent.node().read().unwrap().traverse(&inputs, true, Mode::Eval).unwrap();
 
     //Root
     assert_eq!(tensor_sum(&parent.node().read().unwrap().mesh().layers()[0].lock().unwrap()), 3);
@@ -225,6 +230,7 @@ fn create_links_r_w2n_w2m_all() {
     let mut network_1_0 = Node::new(
         Uuid::new_v4(),
         NodeType::Vertex(
+            VertexKind::Aggregate,
             attribute.clone(),
             Node::new(
                 Uuid::new_v4(),
@@ -260,6 +266,7 @@ fn create_links_r_w2n_w2m_all() {
     let mut network_1_1 = Node::new(
         Uuid::new_v4(),
         NodeType::Vertex(
+            VertexKind::Aggregate,
             attribute.clone(),
             Node::new(
                 Uuid::new_v4(),
@@ -296,6 +303,7 @@ fn create_links_r_w2n_w2m_all() {
         Node::new(
         Uuid::new_v4(),
         NodeType::Vertex(
+            VertexKind::Aggregate,
             attribute.clone(),
             network_1_0,
             attribute.clone()
@@ -312,7 +320,7 @@ fn create_links_r_w2n_w2m_all() {
     let input0 = ("input0",Array::new_empty(dim4!(1,2,1,1)));
     let input1 = ("input1",Array::new_empty(dim4!(1,3,1,1)));
     let inputs = Tensor::new(&[input0, input1]);
-    network_0_0.node().read().unwrap().traverse(&inputs, true);
+    network_0_0.node().read().unwrap().traverse(&inputs, true, Mode::Eval).unwrap();
 
     // Root
     assert_eq!(tensor_sum(&network_0_0.node().read().unwrap().mesh().layers()[0].lock().unwrap()), 5);
@@ -350,6 +358,7 @@ fn create_links_r_w2n_w2m_selective() {
     let mut network_1_0 = Node::new(
         Uuid::new_v4(),
         NodeType::Vertex(
+            VertexKind::Aggregate,
             attribute.clone(),
             Node::new(
                 Uuid::new_v4(),
@@ -385,6 +394,7 @@ fn create_links_r_w2n_w2m_selective() {
     let mut network_1_1 = Node::new(
         Uuid::new_v4(),
         NodeType::Vertex(
+            VertexKind::Aggregate,
             attribute.clone(),
             Node::new(
                 Uuid::new_v4(),
@@ -421,6 +431,7 @@ fn create_links_r_w2n_w2m_selective() {
         Node::new(
         Uuid::new_v4(),
         NodeType::Vertex(
+            VertexKind::Aggregate,
             attribute.clone(),
             network_1_0,
             attribute.clone()
@@ -432,7 +443,7 @@ fn create_links_r_w2n_w2m_selective() {
 
     let input0 = ("input0",Array::new_empty(dim4!(1,3,1,1)));
     let inputs = Tensor::new(&[input0]);
-    network_0_0.node().read().unwrap().traverse(&inputs, true);
+    network_0_0.node().read().unwrap().traverse(&inputs, true, Mode::Eval).unwrap();
 
     // Root
     assert_eq!(tensor_sum(&network_0_0.node().read().unwrap().mesh().layers()[0].lock().unwrap()), 3);
@@ -472,6 +483,7 @@ fn create_links_r_w2n_w2m_wn1out0_all() {
     let mut network_1_0 = Node::new(
         Uuid::new_v4(),
         NodeType::Vertex(
+            VertexKind::Aggregate,
             attribute.clone(),
             Node::new(
                 Uuid::new_v4(),
@@ -510,6 +522,7 @@ fn create_links_r_w2n_w2m_wn1out0_all() {
     let mut network_1_1 = Node::new(
         Uuid::new_v4(),
         NodeType::Vertex(
+            VertexKind::Aggregate,
             attribute.clone(),
             Node::new(
                 Uuid::new_v4(),
@@ -546,6 +559,7 @@ fn create_links_r_w2n_w2m_wn1out0_all() {
         Node::new(
         Uuid::new_v4(),
         NodeType::Vertex(
+            VertexKind::Aggregate,
             attribute.clone(),
             network_1_0,
             attribute.clone()
@@ -555,7 +569,7 @@ fn create_links_r_w2n_w2m_wn1out0_all() {
 
     let input0 = ("input0",Array::new_empty(dim4!(1,3,1,1)));
     let inputs = Tensor::new(&[input0]);
-    network_0_0.node().read().unwrap().traverse(&inputs, true);
+    network_0_0.node().read().unwrap().traverse(&inputs, true, Mode::Eval).unwrap();
 
     // Root
     assert_eq!(tensor_sum(&network_0_0.node().read().unwrap().mesh().layers()[0].lock().unwrap()), 3);
@@ -596,6 +610,7 @@ fn create_links_r_w2n_w2m_wn1out0_selective() {
     let mut network_1_0 = Node::new(
         Uuid::new_v4(),
         NodeType::Vertex(
+            VertexKind::Aggregate,
             attribute.clone(),
             Node::new(Uuid::new_v4(),
                       NodeType::Leaf(
@@ -630,6 +645,7 @@ fn create_links_r_w2n_w2m_wn1out0_selective() {
     let mut network_1_1 = Node::new(
         Uuid::new_v4(),
         NodeType::Vertex(
+        VertexKind::Aggregate,
         attribute.clone(),
         Node::new(Uuid::new_v4(),
                   NodeType::Leaf(
@@ -668,6 +684,7 @@ fn create_links_r_w2n_w2m_wn1out0_selective() {
         Node::new(
         Uuid::new_v4(),
         NodeType::Vertex(
+            VertexKind::Aggregate,
             attribute.clone(),
             network_1_0,
             attribute.clone()
@@ -689,7 +706,7 @@ fn create_links_r_w2n_w2m_wn1out0_selective() {
     let input1 = ("input1",Array::new_empty(dim4!(1,2,1,1)));
     let input2 = ("input2",Array::new_empty(dim4!(1,3,1,1)));
     let inputs = Tensor::new(&[input0, input1, input2]);
-    network_0_0.node().read().unwrap().traverse(&inputs, true);
+    network_0_0.node().read().unwrap().traverse(&inputs, true, Mode::Eval).unwrap();
 
     // Root
     assert_eq!(tensor_sum(&network_0_0.node().read().unwrap().mesh().layers()[0].lock().unwrap()), 6);
@@ -712,4 +729,19 @@ fn create_links_r_w2n_w2m_wn1out0_selective() {
     // Network 2 - child 2
     assert_eq!(tensor_sum(&network_0_0.node().read().unwrap().index_into_node(&[(1,0),(0,1)]).unwrap().lock().unwrap().mesh().layers()[0].lock().unwrap()), 3);
     assert_eq!(tensor_sum(&network_0_0.node().read().unwrap().index_into_node(&[(1,0),(0,1)]).unwrap().lock().unwrap().mesh().layers()[2].lock().unwrap()), 4);
-}
\ No newline at end of file
+}
+#[test]
+fn peer_outputs_same_pass() {
+    let network = Network::new(Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, Attribute::default(), weighted_leaf_node(&[2.0]), Attribute::default())));
+    network.node().write().unwrap().add_child_to_parent(weighted_leaf_node(&[3.0]), None).unwrap();
+    let reader = network.node().read().unwrap().index_into_node(&[(1,0)]).unwrap();
+    assert_eq!(reader.lock().unwrap().mesh().edges().link(InputsFromParent, NodeRange::Selective(vec![])), Ok(()));
+    assert_eq!(reader.lock().unwrap().mesh().edges().link(LinkType::InputsFromPeerOutputs((0,0)), NodeRange::All), Ok(()));
+
+    // The peer is traversed first, so its output reaches the reader on every pass, including the one building it.
+    let inputs = Tensor::new(&[("input0", constant(1.0, dim4!(1,1,1,1)))]);
+    for build in [true, false] {
+        network.node().read().unwrap().traverse(&inputs, build, Mode::Eval).unwrap();
+        assert_eq!(vec![6.0], host(&reader.lock().unwrap().output()));
+    }
+}
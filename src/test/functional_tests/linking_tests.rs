@@ -0,0 +1,69 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use arrayfire::{constant, dim4};
+use uuid::Uuid;
+use crate::node::attribute::Attribute;
+use crate::node::edges::{LinkError, NodeRange};
+use crate::node::processor::processor::Processor;
+use crate::node::mode::Mode;
+use crate::node::node::{Node, NodeType, VertexKind};
+use crate::node::tensor::Tensor;
+use crate::test::functional_tests::helpers::leaf_node;
+
+fn keys(keys: &[&str]) -> NodeRange {
+    NodeRange::Selective(keys.iter().map(|key| key.to_string()).collect())
+}
+
+fn vertex(uuids: &[Uuid; 2]) -> Node {
+    let mut vertex = Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, Attribute::default(), leaf_node(uuids[0], 2), Attribute::default()));
+    vertex.add_child_to_parent(leaf_node(uuids[1], 2), None).unwrap();
+    vertex
+}
+
+#[test]
+fn link_peer_passes_outputs() {
+    let uuids = [Uuid::new_v4(), Uuid::new_v4()];
+    let vertex = vertex(&uuids);
+    vertex.link_peer((0,0), (1,0), keys(&[&uuids[0].to_string()])).unwrap();
+
+    // The source is traversed before the target, so its outputs are read on the same pass.
+    let tensor = Tensor::new(&[("input", constant(1.0, dim4!(1,1,1,1)))]);
+    vertex.traverse(&tensor, true, Mode::Eval).unwrap();
+    let target = vertex.index_into_node(&[(1,0)]).unwrap();
+    let target = target.lock().unwrap();
+    assert!(target.mesh().layers()[0].lock().unwrap().tensor.get(&uuids[0].to_string()).is_some());
+}
+
+#[test]
+fn link_peer_validation() {
+    let uuids = [Uuid::new_v4(), Uuid::new_v4()];
+    let vertex = vertex(&uuids);
+
+    assert_eq!(Err(LinkError::NotVertex), leaf_node(Uuid::new_v4(), 2).link_peer((0,0), (1,0), NodeRange::All));
+    assert_eq!(Err(LinkError::OutOfBounds((0,1))), vertex.link_peer((0,0), (0,1), NodeRange::All));
+    assert_eq!(Err(LinkError::SelfLink), vertex.link_peer((1,0), (1,0), NodeRange::All));
+    assert_eq!(Err(LinkError::UnknownKey(uuids[1].to_string())), vertex.link_peer((0,0), (1,0), keys(&[&uuids[1].to_string()])));
+    vertex.link_peer((0,0), (1,0), NodeRange::All).unwrap();
+    assert_eq!(Err(LinkError::DuplicateLink((0,0))), vertex.link_peer((0,0), (1,0), NodeRange::All));
+}
+
+#[test]
+fn link_parent_validation() {
+    let uuid = Uuid::new_v4();
+    let leaf = leaf_node(uuid, 2);
+
+    assert_eq!(Err(LinkError::DuplicateKey("input".to_string())), leaf.link_input_from_parent(keys(&["input", "input"])));
+    assert_eq!(Err(LinkError::ReservedKey("_SYSTEM_WEIGHTS".to_string())), leaf.link_output_to_parent(keys(&["_SYSTEM_WEIGHTS"])));
+    assert_eq!(Err(LinkError::UnknownKey("other".to_string())), leaf.link_output_to_parent(keys(&["other"])));
+    leaf.link_input_from_parent(keys(&["input"])).unwrap();
+    leaf.link_output_to_parent(keys(&[&uuid.to_string()])).unwrap();
+    assert!(matches!(leaf.mesh().edges().input_from_parent, NodeRange::Selective(_)));
+
+    // The outputs of a vertex are only known once traversed.
+    vertex(&[Uuid::new_v4(), Uuid::new_v4()]).link_output_to_parent(keys(&["other"])).unwrap();
+}
@@ -0,0 +1,55 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use uuid::Uuid;
+use crate::node::attribute::Attribute;
+use crate::node::network::Network;
+use crate::node::node::{Node, NodeType, VertexKind};
+use crate::test::functional_tests::helpers::leaf_node;
+
+/// root -> [encoder -> [embedding, classifier]], [decoder]
+fn named_network(classifier: Uuid) -> Network {
+    let attribute = Attribute::default();
+    let embedding = leaf_node(Uuid::new_v4(), 2).with_name("embedding");
+    let mut encoder = Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, attribute.clone(), embedding, attribute.clone())).with_name("encoder");
+    encoder.add_child_to_parent(leaf_node(classifier, 2).with_name("classifier"), None).unwrap();
+    let mut root = Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, attribute.clone(), encoder, attribute)).with_name("root");
+    root.add_child_to_parent(leaf_node(Uuid::new_v4(), 2).with_name("decoder"), None).unwrap();
+    Network::new(root)
+}
+
+#[test]
+fn find_by_uuid_nested() {
+    let uuid = Uuid::new_v4();
+    let network = named_network(uuid);
+
+    assert_eq!(Some("classifier"), network.find_by_uuid(uuid).unwrap().lock().unwrap().name());
+    assert!(network.find_by_uuid(Uuid::new_v4()).is_none());
+}
+
+#[test]
+fn find_by_path_survives_moves() {
+    let uuid = Uuid::new_v4();
+    let network = named_network(uuid);
+    assert_eq!(uuid, network.find_by_path("root/encoder/classifier").unwrap().lock().unwrap().uuid());
+
+    // Shift the encoder into the second column.
+    network.node().write().unwrap().move_child((0,0), (1,0)).unwrap();
+    assert_eq!(uuid, network.find_by_path("root/encoder/classifier").unwrap().lock().unwrap().uuid());
+    assert_eq!(Some("decoder"), network.find_by_path("root/decoder").unwrap().lock().unwrap().name());
+}
+
+#[test]
+fn find_by_path_segments() {
+    let uuid = Uuid::new_v4();
+    let network = named_network(uuid);
+
+    assert!(network.find_by_path(&format!("root/encoder/{}", uuid)).is_some());
+    assert!(network.find_by_path("root").is_none());
+    assert!(network.find_by_path("encoder/classifier").is_none());
+    assert!(network.find_by_path("root/encoder/missing").is_none());
+}
@@ -0,0 +1,66 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use arrayfire::{Array, constant, dim4};
+use uuid::Uuid;
+use crate::node::attribute::{Activation, Attribute, CellType, Normalization, TensorDescriptor};
+use crate::node::processor::processor::Processor;
+use crate::node::mode::Mode;
+use crate::node::network::Network;
+use crate::node::node::{Node, NodeType};
+use crate::node::scope::Scope;
+use crate::node::tensor::Tensor;
+
+fn normalized_network(normalization: Normalization) -> Network {
+    let attribute = Attribute::new(
+        Activation::None,
+        CellType::Mlp,
+        vec![("_SYSTEM_WEIGHTS", TensorDescriptor::Const(1.0)), ("_SYSTEM_BIASES", TensorDescriptor::Const(0.0))],
+        Scope::new(0,1));
+    Network::new(Node::new(Uuid::new_v4(), NodeType::Leaf(attribute.clone().with_normalization(normalization), vec![(attribute, 1)])))
+}
+
+fn host(array: &Array<f64>) -> Vec<f64> {
+    let mut host = vec![0.0; array.elements()];
+    array.host(&mut host);
+    host
+}
+
+fn input_values(network: &Network, width: usize) -> Vec<f64> {
+    host(network.node().read().unwrap().mesh().tensor().hash_map.get("_SYSTEM_VALUES").unwrap())[..width].to_vec()
+}
+
+fn running_mean(network: &Network) -> Vec<f64> {
+    host(network.node().read().unwrap().mesh().layers()[0].lock().unwrap().tensor.hash_map.get("_SYSTEM_RUNNING_MEAN").unwrap())
+}
+
+#[test]
+fn layer_normalization() {
+    let network = normalized_network(Normalization::Layer);
+    let tensor = Tensor::new(&[("input", Array::new(&[1.0, 2.0, 3.0, 4.0], dim4!(1,4,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+
+    let deviation = (1.25f64 + 1e-5).sqrt();
+    let expected = [-1.5 / deviation, -0.5 / deviation, 0.5 / deviation, 1.5 / deviation];
+    input_values(&network, 4).iter().zip(expected.iter()).for_each(|(value, expected)| assert!((value - expected).abs() < 1e-12));
+}
+
+#[test]
+fn batch_normalization_modes() {
+    let network = normalized_network(Normalization::Batch(0.5));
+    let tensor = Tensor::new(&[("input", constant(2.0, dim4!(1,1,1,1)))]);
+
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Train).unwrap();
+    assert_eq!(vec![1.0], running_mean(&network));
+    assert!((input_values(&network, 1)[0] - 1.0 / (1.0f64 + 1e-5).sqrt()).abs() < 1e-12);
+
+    network.node().read().unwrap().traverse(&tensor, false, Mode::Eval).unwrap();
+    assert_eq!(vec![1.0], running_mean(&network));
+
+    network.node().read().unwrap().traverse(&tensor, false, Mode::Train).unwrap();
+    assert_eq!(vec![1.5], running_mean(&network));
+}
@@ -0,0 +1,93 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use arrayfire::{Array, dim4};
+use uuid::Uuid;
+use crate::node::attribute::{Activation, Attribute, CellType, TensorDescriptor};
+use crate::node::kernel::Kernel;
+use crate::node::processor::processor::Processor;
+use crate::node::mode::Mode;
+use crate::node::network::Network;
+use crate::node::node::{Node, NodeType};
+use crate::node::scope::Scope;
+use crate::node::tensor::Tensor;
+use crate::test::functional_tests::helpers::layer_values;
+
+fn attribute(cell_type: CellType) -> Attribute {
+    Attribute::new(
+        Activation::None,
+        cell_type,
+        vec![("_SYSTEM_KERNELS", TensorDescriptor::Const(1.0)), ("_SYSTEM_KERNEL_BIASES", TensorDescriptor::Const(0.0)),
+             ("_SYSTEM_WEIGHTS", TensorDescriptor::Const(1.0)), ("_SYSTEM_BIASES", TensorDescriptor::Const(0.0))],
+        Scope::new(0,1))
+}
+
+const INPUT: [f64; 6] = [1.0, 3.0, 2.0, 5.0, 4.0, 0.0];
+
+fn pooled_values(cell_type: CellType, input: &[f64]) -> Vec<f64> {
+    let network = Network::new(Node::new(Uuid::new_v4(), NodeType::Leaf(attribute(CellType::Mlp), vec![(attribute(cell_type), 0)])));
+    let tensor = Tensor::new(&[("input", Array::new(input, dim4!(1, input.len() as u64, 1, 1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+    layer_values(&network, 1)
+}
+
+#[test]
+fn max_pool_1d() {
+    assert_eq!(vec![3.0, 5.0, 4.0], pooled_values(CellType::MaxPool(Kernel::new_1d(2, 2, 0, (1, 1))), &INPUT));
+}
+
+#[test]
+fn avg_pool_1d() {
+    assert_eq!(vec![2.0, 3.5, 2.0], pooled_values(CellType::AvgPool(Kernel::new_1d(2, 2, 0, (1, 1))), &INPUT));
+}
+
+#[test]
+fn max_pool_1d_channels() {
+    assert_eq!(vec![3.0, 5.0], pooled_values(CellType::MaxPool(Kernel::new_1d(3, 3, 0, (2, 2))), &INPUT));
+}
+
+#[test]
+fn padded_pools_ignore_padding() {
+    let input = [-1.0, -3.0, -2.0, -5.0, -4.0, -6.0];
+    assert_eq!(vec![-1.0, -2.0, -4.0, -6.0], pooled_values(CellType::MaxPool(Kernel::new_1d(2, 2, 1, (1, 1))), &input));
+    assert_eq!(vec![-1.0, -2.5, -4.5, -6.0], pooled_values(CellType::AvgPool(Kernel::new_1d(2, 2, 1, (1, 1))), &input));
+}
+
+#[test]
+fn global_pools() {
+    assert_eq!(vec![3.0, 5.0], pooled_values(CellType::GlobalMaxPool(2), &INPUT));
+    assert_eq!(vec![2.0, 3.0], pooled_values(CellType::GlobalAvgPool(2), &INPUT));
+}
+
+#[test]
+fn flatten_passes_through() {
+    assert_eq!(vec![1.0, 3.0, 2.0, 5.0, 4.0, 0.0], pooled_values(CellType::Flatten, &INPUT));
+}
+
+#[test]
+fn conv_pool_flatten_dense_topology() {
+    let network = Network::new(Node::new(Uuid::new_v4(), NodeType::Leaf(
+        attribute(CellType::Mlp),
+        vec![
+            (attribute(CellType::Conv2d(Kernel::new((3,3), (1,1), (1,1), (1,2), 4))), 0),
+            (attribute(CellType::MaxPool(Kernel::new((2,2), (2,2), (0,0), (2,2), 4))), 0),
+            (attribute(CellType::Flatten), 0),
+            (attribute(CellType::Mlp), 1),
+        ])));
+
+    let tensor = Tensor::new(&[("input", Array::new_empty(dim4!(1,16,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+
+    assert_eq!(vec![16, 32, 8, 8, 1], network.node().read().unwrap().mesh().topology());
+}
+
+#[test]
+fn pool_channel_mismatch() {
+    let network = Network::new(Node::new(Uuid::new_v4(), NodeType::Leaf(attribute(CellType::Mlp), vec![(attribute(CellType::MaxPool(Kernel::new_1d(2, 2, 0, (1, 2)))), 0)])));
+    let tensor = Tensor::new(&[("input", Array::new(&INPUT, dim4!(1, INPUT.len() as u64, 1, 1)))]);
+    assert_eq!(Err("Pooling kernel must have equal input and output channels."), network.node().read().unwrap().traverse(&tensor, true, Mode::Eval));
+}
@@ -0,0 +1,138 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use arrayfire::{Array, dim4};
+use uuid::Uuid;
+use crate::node::attribute::{Activation, Attribute, CellType, TensorDescriptor};
+use crate::node::processor::processor::Processor;
+use crate::node::mode::Mode;
+use crate::node::network::Network;
+use crate::node::node::{Node, NodeType};
+use crate::node::scope::Scope;
+use crate::node::tensor::Tensor;
+use crate::test::functional_tests::helpers::{dense_attribute, host, layer_values};
+
+fn som_weights(network: &Network) -> Vec<f64> {
+    host(network.node().read().unwrap().mesh().layers()[1].lock().unwrap().tensor.hash_map.get("_SYSTEM_SOM_WEIGHTS").unwrap())
+}
+
+fn som_network() -> Network {
+    let som = Attribute::new(
+        Activation::None,
+        CellType::Som(2, 1, 0.5, 1.0),
+        vec![("_SYSTEM_SOM_WEIGHTS", TensorDescriptor::Values(vec![0.0, 4.0], vec![2,1]))],
+        Scope::new(0,1));
+    Network::new(Node::new(Uuid::new_v4(), NodeType::Leaf(dense_attribute(), vec![(som, 0)])))
+}
+
+fn rbf_network(width: f64) -> Network {
+    let rbf = Attribute::new(
+        Activation::None,
+        CellType::Rbf(2),
+        vec![("_SYSTEM_RBF_CENTERS", TensorDescriptor::Values(vec![1.0, 0.0, 2.0, 0.0], vec![2,2])),
+             ("_SYSTEM_RBF_WIDTHS", TensorDescriptor::Const(width))],
+        Scope::new(0,1));
+    Network::new(Node::new(Uuid::new_v4(), NodeType::Leaf(dense_attribute(), vec![(rbf, 0)])))
+}
+
+#[test]
+fn rbf_values() {
+    let network = rbf_network(1.0);
+    let tensor = Tensor::new(&[("input", Array::new(&[1.0, 2.0], dim4!(1,2,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+
+    assert_eq!(vec![2, 2], network.node().read().unwrap().mesh().topology());
+    let expected = [1.0, (-2.5f64).exp()];
+    layer_values(&network, 1).iter().zip(expected.iter()).for_each(|(value, expected)| assert!((value - expected).abs() < 1e-12));
+}
+
+#[test]
+fn rbf_negative_widths() {
+    let network = rbf_network(-1.0);
+    let tensor = Tensor::new(&[("input", Array::new(&[1.0, 2.0], dim4!(1,2,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+
+    let expected = [1.0, (-2.5f64).exp()];
+    layer_values(&network, 1).iter().zip(expected.iter()).for_each(|(value, expected)| assert!((value - expected).abs() < 1e-12));
+}
+
+#[test]
+fn rbf_zero_width() {
+    let network = rbf_network(0.0);
+    let tensor = Tensor::new(&[("input", Array::new(&[1.0, 2.0], dim4!(1,2,1,1)))]);
+    assert_eq!(Err("Radial basis function widths must not be zero."), network.node().read().unwrap().traverse(&tensor, true, Mode::Eval));
+}
+
+#[test]
+fn rbf_negative_explicit_widths() {
+    let rbf = Attribute::new(
+        Activation::None,
+        CellType::Rbf(2),
+        vec![("_SYSTEM_RBF_CENTERS", TensorDescriptor::Const(0.0)), ("_SYSTEM_RBF_WIDTHS", TensorDescriptor::Values(vec![1.0, -1.0], vec![2,1]))],
+        Scope::new(0,1));
+    let network = Network::new(Node::new(Uuid::new_v4(), NodeType::Leaf(dense_attribute(), vec![(rbf, 0)])));
+    let tensor = Tensor::new(&[("input", Array::new(&[1.0, 2.0], dim4!(1,2,1,1)))]);
+    assert_eq!(Err("Radial basis function widths must not be negative."), network.node().read().unwrap().traverse(&tensor, true, Mode::Eval));
+}
+
+#[test]
+fn som_winner_eval() {
+    let network = som_network();
+    let tensor = Tensor::new(&[("input", Array::new(&[3.0], dim4!(1,1,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+
+    assert_eq!(vec![0.0, 1.0], layer_values(&network, 1));
+    assert_eq!(vec![0.0, 4.0], som_weights(&network));
+}
+
+#[test]
+fn som_neighbourhood_train() {
+    let network = som_network();
+    let tensor = Tensor::new(&[("input", Array::new(&[3.0], dim4!(1,1,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Train).unwrap();
+
+    let neighbour = 0.5 * (-0.5f64).exp() * 3.0;
+    let expected = [neighbour, 3.5];
+    som_weights(&network).iter().zip(expected.iter()).for_each(|(value, expected)| assert!((value - expected).abs() < 1e-12));
+}
+
+#[test]
+fn rbf_train() {
+    let rbf = Attribute::new(
+        Activation::None,
+        CellType::Rbf(1),
+        vec![("_SYSTEM_RBF_CENTERS", TensorDescriptor::Const(0.0)), ("_SYSTEM_RBF_WIDTHS", TensorDescriptor::Const(1.0))],
+        Scope::new(0,1));
+    let network = Network::new(Node::new(Uuid::new_v4(), NodeType::Leaf(dense_attribute(), vec![(rbf, 0)])));
+    let tensor = Tensor::new(&[("input", Array::new(&[1.0], dim4!(1,1,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+    let loss = network.node().read().unwrap().train(&tensor, &Array::new(&[1.0], dim4!(1,1,1,1)), 0.1).unwrap();
+
+    // The output exp(-1/2) misses the target by g, and both the center and the width receive g * exp(-1/2).
+    let output = (-0.5f64).exp();
+    let gradient = (output - 1.0) * output;
+    assert!((loss - 0.5 * (output - 1.0).powi(2)).abs() < 1e-12);
+    let node = network.node();
+    let node = node.read().unwrap();
+    let mesh = node.mesh();
+    let layer = mesh.layers()[1].lock().unwrap();
+    assert!((host(layer.tensor.hash_map.get("_SYSTEM_RBF_CENTERS").unwrap())[0] + 0.1 * gradient).abs() < 1e-12);
+    assert!((host(layer.tensor.hash_map.get("_SYSTEM_RBF_WIDTHS").unwrap())[0] - (1.0 - 0.1 * gradient)).abs() < 1e-12);
+}
+
+#[test]
+fn som_train() {
+    let network = som_network();
+    let tensor = Tensor::new(&[("input", Array::new(&[3.0], dim4!(1,1,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+    let loss = network.node().read().unwrap().train(&tensor, &Array::new(&[0.0, 1.0], dim4!(1,2,1,1)), 0.1).unwrap();
+
+    assert_eq!(0.0, loss);
+    let neighbour = 0.5 * (-0.5f64).exp() * 3.0;
+    let expected = [neighbour, 3.5];
+    som_weights(&network).iter().zip(expected.iter()).for_each(|(value, expected)| assert!((value - expected).abs() < 1e-12));
+}
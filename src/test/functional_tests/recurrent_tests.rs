@@ -0,0 +1,85 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use arrayfire::{constant, dim4};
+use uuid::Uuid;
+use crate::node::attribute::{Activation, Attribute, CellType, TensorDescriptor};
+use crate::node::processor::processor::Processor;
+use crate::node::mode::Mode;
+use crate::node::network::Network;
+use crate::node::node::{Node, NodeType};
+use crate::node::scope::Scope;
+use crate::node::tensor::Tensor;
+
+fn sigmoid(x: f64) -> f64 { 1.0 / (1.0 + (-x).exp()) }
+
+fn recurrent_network(cell_type: CellType) -> Network {
+    let input = Attribute::new(
+        Activation::None,
+        CellType::Mlp,
+        vec![("_SYSTEM_WEIGHTS", TensorDescriptor::Const(1.0)), ("_SYSTEM_BIASES", TensorDescriptor::Const(0.0))],
+        Scope::new(0,1));
+    let recurrent = Attribute::new(
+        Activation::None,
+        cell_type,
+        vec![("_SYSTEM_GATE_WEIGHTS", TensorDescriptor::Const(0.5)), ("_SYSTEM_GATE_BIASES", TensorDescriptor::Const(0.0))],
+        Scope::new(0,1));
+
+    Network::new(
+        Node::new(
+            Uuid::new_v4(),
+            NodeType::Leaf(
+                input,
+                vec![
+                    (recurrent, 1),
+                ]
+            )))
+}
+
+fn step(network: &Network, build: bool) -> f64 {
+    let tensor = Tensor::new(&[("input", constant(1.0,dim4!(1,1,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, build, Mode::Eval).unwrap();
+    let mut host = vec![0.0; 2];
+    network.node().read().unwrap().mesh().tensor().hash_map.get("_SYSTEM_VALUES").unwrap().host(&mut host);
+    host[1]
+}
+
+#[test]
+fn lstm_carries_state() {
+    let network = recurrent_network(CellType::Lstm);
+
+    let (mut hidden, mut cell) = (0.0, 0.0);
+    for build in [true, false, false] {
+        let pre = 0.5 + 0.5 * hidden;
+        cell = sigmoid(pre) * cell + sigmoid(pre) * pre.tanh();
+        hidden = sigmoid(pre) * cell.tanh();
+        assert!((hidden - step(&network, build)).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn gru_carries_state() {
+    let network = recurrent_network(CellType::Gru);
+
+    let mut hidden: f64 = 0.0;
+    for build in [true, false, false] {
+        let pre = 0.5 + 0.5 * hidden;
+        let candidate = (0.5 + 0.5 * sigmoid(pre) * hidden).tanh();
+        hidden = candidate + sigmoid(pre) * (hidden - candidate);
+        assert!((hidden - step(&network, build)).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn reset_state_restores_first_step() {
+    let network = recurrent_network(CellType::Lstm);
+
+    let first = step(&network, true);
+    assert_ne!(first, step(&network, false));
+    network.node().read().unwrap().reset_state();
+    assert_eq!(first, step(&network, false));
+}
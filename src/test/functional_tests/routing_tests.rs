@@ -0,0 +1,70 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use arrayfire::{constant, dim4, ge, lt};
+use uuid::Uuid;
+use crate::node::attribute::Attribute;
+use crate::node::function::{Function, register_function};
+use crate::node::processor::processor::Processor;
+use crate::node::mode::Mode;
+use crate::node::network::Network;
+use crate::node::node::{Node, NodeType, Routing, VertexKind};
+use crate::node::tensor::Tensor;
+use crate::test::functional_tests::helpers::{host, weighted_leaf_node};
+
+/// Routes negative inputs to the first route and all others to the second.
+struct SignRouter;
+
+impl Function for SignRouter {
+    fn name(&self) -> &str { "sign_router" }
+
+    fn call(&self, inputs: &Tensor) -> Result<Tensor, &'static str> {
+        let input = inputs.get("input").ok_or("Missing input.")?;
+        let scores = arrayfire::join(1, &lt(input, &0.0, false).cast::<f64>(), &ge(input, &0.0, false).cast::<f64>());
+        Ok(Tensor::new(&[("scores", scores)]))
+    }
+}
+
+fn routed_network(routing: Routing) -> Network {
+    register_function("sign_router", |_| Ok(Box::new(SignRouter)));
+    let router = Node::new(Uuid::new_v4(), NodeType::function(&SignRouter));
+    let network = Network::new(Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Routed(routing), Attribute::default(), router, Attribute::default())));
+    network.node().write().unwrap().add_child_to_parent(weighted_leaf_node(&[1.0, 2.0]), None).unwrap();
+    network.node().write().unwrap().add_child_to_parent(weighted_leaf_node(&[3.0]), None).unwrap();
+    network
+}
+
+fn traverse(network: &Network, input: f64, build: bool) {
+    let tensor = Tensor::new(&[("input", constant(input, dim4!(1,1,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, build, Mode::Eval).unwrap();
+}
+
+#[test]
+fn routing_selects_route() {
+    let network = routed_network(Routing::Best);
+
+    traverse(&network, 1.0, true);
+    assert_eq!(Some(vec![2]), network.node().read().unwrap().route());
+    assert_eq!(vec![3.0], host(&network.node().read().unwrap().output()));
+
+    traverse(&network, -1.0, false);
+    assert_eq!(Some(vec![1]), network.node().read().unwrap().route());
+    assert_eq!(vec![-1.0, -2.0], host(&network.node().read().unwrap().output()));
+
+    // The route not taken was left untouched.
+    let skipped = network.node().read().unwrap().index_into_node(&[(2,0)]).unwrap();
+    assert_eq!(vec![3.0], host(&skipped.lock().unwrap().output()));
+}
+
+#[test]
+fn routing_threshold_selects_routes() {
+    let network = routed_network(Routing::Threshold(0.0));
+
+    traverse(&network, 1.0, true);
+    assert_eq!(Some(vec![1, 2]), network.node().read().unwrap().route());
+    assert_eq!(3, network.node().read().unwrap().output().elements());
+}
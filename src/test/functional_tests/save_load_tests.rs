@@ -9,6 +9,7 @@ use arrayfire::{constant, dim4};
 use uuid::Uuid;
 use crate::node::attribute::{Activation, Attribute, CellType, TensorDescriptor};
 use crate::node::processor::processor::Processor;
+use crate::node::mode::Mode;
 use crate::node::network::Network;
 use crate::node::node::{Node, NodeType};
 use crate::node::scope::Scope;
@@ -26,7 +27,7 @@ fn save_default() {
         Scope::new(0,1));
 
 
-    let mut network = Network::new(
+    let network = Network::new(
         Node::new(
             Uuid::from_u128(0),
             NodeType::Leaf(
@@ -38,7 +39,7 @@ fn save_default() {
             )));
 
     let tensor = Tensor::new(&[("input", constant(1.0,dim4!(1,1,1,1)))]);
-    network.node().read().unwrap().traverse(&tensor, true);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
 
     let expected : Vec<u8> = vec![2, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0,
                                   0, 0, 0, 0, 6, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0, 0, 0, 0, 0, 0, 154, 153, 153, 153, 153, 153,
@@ -54,7 +55,7 @@ fn save_default() {
 fn load_default() {
     let network = Network::load("00000000-0000-0000-0000-000000000000.annml").unwrap();
     let tensor = Tensor::new(&[("input", constant(1.0,dim4!(1,1,1,1)))]);
-    network.node().read().unwrap().traverse(&tensor, true);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
 
     let expected : Vec<u8> = vec![2, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0,
                                   0, 0, 0, 0, 6, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0, 0, 0, 0, 0, 0, 154, 153, 153, 153, 153, 153,
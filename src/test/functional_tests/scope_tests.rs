@@ -9,6 +9,7 @@ use arrayfire::{Array, dim4};
 use uuid::Uuid;
 use crate::node::attribute::{Activation, Attribute, CellType, TensorDescriptor};
 use crate::node::processor::processor::Processor;
+use crate::node::mode::Mode;
 use crate::node::network::Network;
 use crate::node::node::{Node, NodeType};
 use crate::node::scope::Scope;
@@ -36,7 +37,7 @@ fn create_layers_0_1(){
         )));
 
     let tensor = Tensor::new(&[("input", Array::new_empty(dim4!(1,2,1,1)))]);
-    network.node().read().unwrap().traverse(&tensor, true);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
 
     assert_eq!((0,0,0,1,0),network.node().read().unwrap().mesh().layers()[0].lock().unwrap().domain.domain_data());
     assert_eq!((0,1,1,2,0),network.node().read().unwrap().mesh().layers()[1].lock().unwrap().domain.domain_data());
@@ -68,7 +69,7 @@ fn create_layers_0_2(){
         )));
 
     let tensor = Tensor::new(&[("input", Array::new_empty(dim4!(1,2,1,1)))]);
-    network.node().read().unwrap().traverse(&tensor, true);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
 
     assert_eq!((0,0,0,2,0),network.node().read().unwrap().mesh().layers()[0].lock().unwrap().domain.domain_data());
     assert_eq!((0,1,1,3,0),network.node().read().unwrap().mesh().layers()[1].lock().unwrap().domain.domain_data());
@@ -100,7 +101,7 @@ fn create_layers_0_3(){
         )));
 
     let tensor = Tensor::new(&[("input", Array::new_empty(dim4!(1,2,1,1)))]);
-    network.node().read().unwrap().traverse(&tensor, true);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
 
     assert_eq!((0,0,0,3,0),network.node().read().unwrap().mesh().layers()[0].lock().unwrap().domain.domain_data());
     assert_eq!((0,1,1,4,0),network.node().read().unwrap().mesh().layers()[1].lock().unwrap().domain.domain_data());
@@ -132,7 +133,7 @@ fn create_layers_1_1(){
         )));
 
     let tensor = Tensor::new(&[("input", Array::new_empty(dim4!(1,2,1,1)))]);
-    network.node().read().unwrap().traverse(&tensor, true);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
 
     assert_eq!((1,0,0,1,0),network.node().read().unwrap().mesh().layers()[0].lock().unwrap().domain.domain_data());
     assert_eq!((0,0,1,2,0),network.node().read().unwrap().mesh().layers()[1].lock().unwrap().domain.domain_data());
@@ -164,7 +165,7 @@ fn create_layers_2_2(){
         )));
 
     let tensor = Tensor::new(&[("input", Array::new_empty(dim4!(1,2,1,1)))]);
-    network.node().read().unwrap().traverse(&tensor, true);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
 
     assert_eq!((2,0,0,2,0),network.node().read().unwrap().mesh().layers()[0].lock().unwrap().domain.domain_data());
     assert_eq!((1,0,1,3,0),network.node().read().unwrap().mesh().layers()[1].lock().unwrap().domain.domain_data());
@@ -196,7 +197,7 @@ fn create_layers_3_3(){
         )));
 
     let tensor = Tensor::new(&[("input", Array::new_empty(dim4!(1,2,1,1)))]);
-    network.node().read().unwrap().traverse(&tensor, true);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
 
     assert_eq!((3,0,0,3,0),network.node().read().unwrap().mesh().layers()[0].lock().unwrap().domain.domain_data());
     assert_eq!((2,0,1,4,0),network.node().read().unwrap().mesh().layers()[1].lock().unwrap().domain.domain_data());
@@ -0,0 +1,81 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use arrayfire::{Array, dim4};
+use uuid::Uuid;
+use crate::node::attribute::{Activation, Attribute, CellType, TensorDescriptor};
+use crate::node::processor::processor::Processor;
+use crate::node::mode::Mode;
+use crate::node::network::Network;
+use crate::node::node::{Node, NodeType};
+use crate::node::scope::Scope;
+use crate::node::tensor::Tensor;
+use crate::node::utils::stream_seed;
+
+fn seeded_network(uuid: u128, seed: u64) -> Network {
+    let attribute = Attribute::new(
+        Activation::None,
+        CellType::Mlp,
+        vec![("_SYSTEM_WEIGHTS", TensorDescriptor::RandN), ("_SYSTEM_BIASES", TensorDescriptor::RangeU(2.0))],
+        Scope::new(0,2));
+
+    let network = Network::with_seed(
+        Node::new(
+            Uuid::from_u128(uuid),
+            NodeType::Leaf(
+                attribute.clone(),
+                vec![
+                    (attribute.clone(), 3),
+                    (attribute.clone(), 4),
+                    (attribute.clone(), 2),
+                ]
+            )), seed);
+
+    let tensor = Tensor::new(&[("input", Array::new_empty(dim4!(1,2,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+    network
+}
+
+fn layer_bytes(network: &Network, index: usize, key: &str) -> Vec<u8> {
+    bincode::serialize(network.node().read().unwrap().mesh().layers()[index].lock().unwrap().tensor.hash_map.get(key).unwrap()).unwrap()
+}
+
+#[test]
+fn same_seed_same_values() {
+    let first = seeded_network(1, 7);
+    let second = seeded_network(1, 7);
+    for index in 0..3 {
+        assert_eq!(layer_bytes(&first, index, "_SYSTEM_WEIGHTS"), layer_bytes(&second, index, "_SYSTEM_WEIGHTS"));
+        assert_eq!(layer_bytes(&first, index, "_SYSTEM_BIASES"), layer_bytes(&second, index, "_SYSTEM_BIASES"));
+    }
+}
+
+#[test]
+fn different_seed_different_values() {
+    let first = seeded_network(1, 7);
+    let second = seeded_network(1, 8);
+    assert_ne!(layer_bytes(&first, 0, "_SYSTEM_WEIGHTS"), layer_bytes(&second, 0, "_SYSTEM_WEIGHTS"));
+}
+
+#[test]
+fn different_uuid_different_values() {
+    let first = seeded_network(1, 7);
+    let second = seeded_network(2, 7);
+    assert_ne!(layer_bytes(&first, 0, "_SYSTEM_WEIGHTS"), layer_bytes(&second, 0, "_SYSTEM_WEIGHTS"));
+}
+
+#[test]
+fn layers_draw_independent_streams() {
+    let network = seeded_network(1, 7);
+    assert_ne!(layer_bytes(&network, 0, "_SYSTEM_WEIGHTS"), layer_bytes(&network, 1, "_SYSTEM_WEIGHTS"));
+}
+
+#[test]
+fn stream_seeds_are_stable() {
+    assert_eq!(2391539541053276776, stream_seed(0, 0, 0));
+    assert_eq!(18225635257843705144, stream_seed(42, 1, 2));
+}
@@ -0,0 +1,155 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use arrayfire::{constant, dim4};
+use uuid::Uuid;
+use crate::node::attribute::{Aggregation, Attribute};
+use crate::node::edges::{LinkType, NodeRange};
+use crate::node::processor::processor::Processor;
+use crate::node::mode::Mode;
+use crate::node::node::{Node, NodeType, VertexKind};
+use crate::node::tensor::Tensor;
+use crate::test::functional_tests::helpers::{host, leaf_node, peer_links, weighted_leaf_node};
+
+/// Vertex with one child per column, the last child reading the outputs of the two others.
+fn linked_vertex(uuids: &[Uuid; 3]) -> Node {
+    let mut vertex = Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, Attribute::default(), leaf_node(uuids[0], 2), Attribute::default()));
+    vertex.add_child_to_parent(leaf_node(uuids[1], 2), None).unwrap();
+    vertex.add_child_to_parent(leaf_node(uuids[2], 2), None).unwrap();
+    let last = vertex.index_into_node(&[(2,0)]).unwrap();
+    last.lock().unwrap().mesh().edges().link(LinkType::InputsFromPeerOutputs((0,0)), NodeRange::All).unwrap();
+    last.lock().unwrap().mesh().edges().link(LinkType::InputsFromPeerOutputs((1,0)), NodeRange::All).unwrap();
+    vertex
+}
+
+#[test]
+fn remove_child_rewrites_links() {
+    let uuids = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+    let mut vertex = linked_vertex(&uuids);
+
+    let removed = vertex.remove_child((0,0)).unwrap();
+    assert_eq!(uuids[0], removed.uuid());
+    assert_eq!(2, vertex.children().len());
+    assert_eq!(uuids[2], vertex.index_into_node(&[(1,0)]).unwrap().lock().unwrap().uuid());
+    assert_eq!(vec![(0,0)], peer_links(&vertex.index_into_node(&[(1,0)]).unwrap()));
+}
+
+/// Vertex over two leaves turning a single input into `first` and `second`, after a first pass building it.
+fn built_vertex(first: &[f64], second: &[f64], output: Attribute) -> Node {
+    let mut vertex = Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, Attribute::default(), weighted_leaf_node(first), output));
+    vertex.add_child_to_parent(weighted_leaf_node(second), None).unwrap();
+    vertex.traverse(&input(), true, Mode::Eval).unwrap();
+    vertex
+}
+
+fn input() -> Tensor {
+    Tensor::new(&[("input", constant(1.0, dim4!(1,1,1,1)))])
+}
+
+#[test]
+fn remove_child_removes_outputs() {
+    let mut vertex = built_vertex(&[1.0, 2.0], &[4.0], Attribute::default());
+    assert_eq!(3, vertex.output().elements());
+
+    vertex.remove_child((0,0)).unwrap();
+    assert_eq!(vec![4.0], host(&vertex.output()));
+    vertex.traverse(&input(), false, Mode::Eval).unwrap();
+    assert_eq!(vec![4.0], host(&vertex.output()));
+}
+
+#[test]
+fn remove_child_rebuilds_aggregation_weights() {
+    let mut vertex = built_vertex(&[2.0], &[4.0], Attribute::default().with_aggregation(Aggregation::WeightedMean));
+    assert_eq!(vec![3.0], host(&vertex.output()));
+
+    // The weights of the removed child are dropped, so the remaining child is weighted alone.
+    vertex.remove_child((0,0)).unwrap();
+    vertex.traverse(&input(), false, Mode::Eval).unwrap();
+    assert_eq!(vec![4.0], host(&vertex.output()));
+}
+
+#[test]
+fn remove_child_clears_its_links() {
+    let uuids = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+    let mut vertex = linked_vertex(&uuids);
+
+    let removed = vertex.remove_child((2,0)).unwrap();
+    assert!(removed.mesh().edges().input_from_peer_output.is_empty());
+}
+
+#[test]
+fn move_child_rewrites_links() {
+    let uuids = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+    let mut vertex = linked_vertex(&uuids);
+
+    vertex.move_child((1,0), (0,0)).unwrap();
+    assert_eq!(2, vertex.children().len());
+    assert_eq!(uuids[1], vertex.index_into_node(&[(0,0)]).unwrap().lock().unwrap().uuid());
+    assert_eq!(uuids[0], vertex.index_into_node(&[(0,1)]).unwrap().lock().unwrap().uuid());
+    assert_eq!(vec![(0,1), (0,0)], peer_links(&vertex.index_into_node(&[(1,0)]).unwrap()));
+
+    // Moving the linking child follows its own column changes.
+    vertex.move_child((1,0), (0,2)).unwrap();
+    assert_eq!(1, vertex.children().len());
+    assert_eq!(vec![(0,1), (0,0)], peer_links(&vertex.index_into_node(&[(0,2)]).unwrap()));
+}
+
+#[test]
+fn replace_child_keeps_links() {
+    let uuids = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+    let mut vertex = linked_vertex(&uuids);
+
+    let replacement = Uuid::new_v4();
+    let replaced = vertex.replace_child((0,0), leaf_node(replacement, 2)).unwrap();
+    assert_eq!(uuids[0], replaced.uuid());
+    assert_eq!(replacement, vertex.index_into_node(&[(0,0)]).unwrap().lock().unwrap().uuid());
+    assert_eq!(vec![(0,0), (1,0)], peer_links(&vertex.index_into_node(&[(2,0)]).unwrap()));
+}
+
+#[test]
+fn replace_child_transfers_links() {
+    let uuids = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+    let mut vertex = linked_vertex(&uuids);
+
+    let replacement = Uuid::new_v4();
+    let replaced = vertex.replace_child((2,0), leaf_node(replacement, 2)).unwrap();
+    assert_eq!(vec![(0,0), (1,0)], peer_links(&vertex.index_into_node(&[(2,0)]).unwrap()));
+    assert!(replaced.mesh().edges().input_from_peer_output.is_empty());
+}
+
+#[test]
+fn replaced_child_receives_inputs() {
+    let reader = weighted_leaf_node(&[3.0]);
+    reader.mesh().edges().link(LinkType::InputsFromParent, NodeRange::Selective(vec!["input".to_string()])).unwrap();
+    let mut vertex = Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, Attribute::default(), reader, Attribute::default()));
+
+    // Without the parent link, the replacement would receive both inputs and fail to build.
+    vertex.replace_child((0,0), weighted_leaf_node(&[5.0])).unwrap();
+    let tensor = Tensor::new(&[("input", constant(1.0, dim4!(1,1,1,1))), ("ignored", constant(7.0, dim4!(1,1,1,1)))]);
+    vertex.traverse(&tensor, true, Mode::Eval).unwrap();
+    let replacement = vertex.index_into_node(&[(0,0)]).unwrap();
+    let replacement = replacement.lock().unwrap();
+    assert_eq!(vec![5.0], host(replacement.mesh().tensor().hash_map.get(&replacement.uuid().to_string()).unwrap()));
+}
+
+#[test]
+fn structural_edit_errors() {
+    let uuids = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+    let mut vertex = linked_vertex(&uuids);
+
+    assert_eq!(Err("Child index out of bounds."), vertex.remove_child((3,0)).map(|_| ()));
+    assert_eq!(Err("Child index out of bounds."), vertex.move_child((0,0), (0,2)));
+    let held = vertex.index_into_node(&[(1,0)]).unwrap();
+    assert_eq!(Err("Child is still referenced."), vertex.remove_child((1,0)).map(|_| ()));
+    drop(held);
+
+    let mut leaf = leaf_node(Uuid::new_v4(), 2);
+    assert_eq!(Err("Unable to edit children of leaf."), leaf.remove_child((0,0)).map(|_| ()));
+
+    let mut single = Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, Attribute::default(), leaf_node(Uuid::new_v4(), 2), Attribute::default()));
+    assert_eq!(Err("Unable to remove the last child of a vertex."), single.remove_child((0,0)).map(|_| ()));
+}
@@ -0,0 +1,65 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use arrayfire::{constant, dim4};
+use uuid::Uuid;
+use crate::node::attribute::{Aggregation, Attribute, Normalization};
+use crate::node::processor::processor::Processor;
+use crate::node::mode::Mode;
+use crate::node::network::Network;
+use crate::node::node::{Node, NodeType, VertexKind};
+use crate::node::summary::NodeKind;
+use crate::node::tensor::Tensor;
+use crate::test::functional_tests::helpers::dense_attribute;
+
+/// Named leaf projecting its inputs onto a batch normalized layer of 2 values.
+fn leaf_node(uuid: Uuid) -> Node {
+    let hidden = Attribute::default().with_normalization(Normalization::Batch(0.9));
+    Node::new(uuid, NodeType::Leaf(dense_attribute(), vec![(hidden, 2)])).with_name("classifier")
+}
+
+fn built_network(uuid: Uuid) -> Network {
+    let network = Network::new(Node::new(Uuid::new_v4(), NodeType::Vertex(VertexKind::Aggregate, Attribute::default(), leaf_node(uuid), Attribute::default())));
+    let tensor = Tensor::new(&[("input", constant(1.0, dim4!(1,3,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+    network
+}
+
+#[test]
+fn summary_structure() {
+    let uuid = Uuid::new_v4();
+    let summary = built_network(uuid).summary();
+
+    assert_eq!(2, summary.nodes().len());
+    assert_eq!(&NodeKind::Vertex(VertexKind::Aggregate, Aggregation::Concatenate), summary.nodes()[0].kind());
+    let leaf = &summary.nodes()[1];
+    assert_eq!((&NodeKind::Leaf, 1, Some((0,0))), (leaf.kind(), leaf.depth(), leaf.position()));
+    assert_eq!((uuid, Some("classifier")), (leaf.uuid(), leaf.name()));
+    assert_eq!(&vec!["input".to_string()], leaf.inputs());
+    assert_eq!(&vec![uuid.to_string()], leaf.outputs());
+    assert_eq!(vec![3, 2], leaf.layers().iter().map(|layer| layer.width()).collect::<Vec<usize>>());
+    assert!(summary.to_string().contains("classifier"));
+}
+
+#[test]
+fn summary_parameters() {
+    let uuid = Uuid::new_v4();
+    let network = built_network(uuid);
+    let summary = network.summary();
+
+    let leaf = network.find_by_uuid(uuid).unwrap();
+    let leaf = leaf.lock().unwrap();
+    let mesh = leaf.mesh();
+    let input = mesh.layers()[0].lock().unwrap();
+    let projection = input.tensor.get("_SYSTEM_WEIGHTS").unwrap().elements() + input.tensor.get("_SYSTEM_BIASES").unwrap().elements();
+
+    // The running statistics of the normalization are state, only its scale and shift are learnable.
+    assert_eq!(projection, summary.nodes()[1].layers()[0].parameters());
+    assert_eq!(4, summary.nodes()[1].layers()[1].parameters());
+    assert_eq!(projection + 4, summary.parameters());
+    assert!(summary.memory() >= 8 * summary.parameters());
+}
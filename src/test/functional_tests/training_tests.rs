@@ -0,0 +1,85 @@
+/*
+ * Author:    Christian Potts
+ * Created:   March 13th, 2022
+ *
+ * (c) Copyright by Christian Potts
+ */
+
+use arrayfire::{Array, dim4};
+use uuid::Uuid;
+use crate::node::attribute::{Activation, Attribute, CellType, Normalization, TensorDescriptor};
+use crate::node::processor::processor::Processor;
+use crate::node::mode::Mode;
+use crate::node::network::Network;
+use crate::node::node::{Node, NodeType, VertexKind};
+use crate::node::scope::Scope;
+use crate::node::tensor::Tensor;
+use crate::test::functional_tests::helpers::{dense_attribute, layer_values};
+
+fn half_network() -> Network {
+    let attribute = Attribute::new(
+        Activation::None,
+        CellType::Mlp,
+        vec![("_SYSTEM_WEIGHTS", TensorDescriptor::Const(0.5)), ("_SYSTEM_BIASES", TensorDescriptor::Const(0.0))],
+        Scope::new(0,1));
+    Network::new(Node::new(Uuid::new_v4(), NodeType::Leaf(attribute.clone(), vec![(attribute, 1)])))
+}
+
+#[test]
+fn mlp_train() {
+    let network = half_network();
+    let tensor = Tensor::new(&[("input", Array::new(&[2.0], dim4!(1,1,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+
+    // The output 1 misses the target by -2, so the weight moves by 0.1 * 2 * 2 and the bias by 0.1 * 2.
+    let loss = network.node().read().unwrap().train(&tensor, &Array::new(&[3.0], dim4!(1,1,1,1)), 0.1).unwrap();
+    assert_eq!(2.0, loss);
+    network.node().read().unwrap().traverse(&tensor, false, Mode::Eval).unwrap();
+    assert!((layer_values(&network, 1)[0] - 2.0).abs() < 1e-12);
+}
+
+#[test]
+fn mlp_train_converges() {
+    let network = half_network();
+    let tensor = Tensor::new(&[("input", Array::new(&[2.0], dim4!(1,1,1,1)))]);
+    let target = Array::new(&[3.0], dim4!(1,1,1,1));
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+
+    let losses = (0..20).map(|_| network.node().read().unwrap().train(&tensor, &target, 0.1).unwrap()).collect::<Vec<f64>>();
+    assert!(losses.windows(2).all(|pair| pair[1] < pair[0]));
+    assert!(*losses.last().unwrap() < 1e-6);
+}
+
+#[test]
+fn train_unbuilt() {
+    let network = half_network();
+    let tensor = Tensor::new(&[("input", Array::new(&[2.0], dim4!(1,1,1,1)))]);
+    assert_eq!(Err("Node must be built before training."), network.node().read().unwrap().train(&tensor, &Array::new(&[3.0], dim4!(1,1,1,1)), 0.1));
+}
+
+#[test]
+fn train_vertex() {
+    let network = Network::new(Node::new(
+        Uuid::new_v4(),
+        NodeType::Vertex(VertexKind::Aggregate, dense_attribute(), Node::new(Uuid::new_v4(), NodeType::Leaf(dense_attribute(), vec![(dense_attribute(), 1)])), dense_attribute())));
+    let tensor = Tensor::new(&[("input", Array::new(&[2.0], dim4!(1,1,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+    assert_eq!(Err("Only leaf Nodes can be trained."), network.node().read().unwrap().train(&tensor, &Array::new(&[3.0], dim4!(1,1,1,1)), 0.1));
+}
+
+#[test]
+fn train_normalized_layer() {
+    let normalized = dense_attribute().with_normalization(Normalization::Layer);
+    let network = Network::new(Node::new(Uuid::new_v4(), NodeType::Leaf(dense_attribute(), vec![(normalized, 2)])));
+    let tensor = Tensor::new(&[("input", Array::new(&[2.0], dim4!(1,1,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+    assert_eq!(Err("Only Mlp, Rbf, Som and custom layers without normalization can be trained."), network.node().read().unwrap().train(&tensor, &Array::new(&[1.0, 2.0], dim4!(1,2,1,1)), 0.1));
+}
+
+#[test]
+fn train_target_mismatch() {
+    let network = half_network();
+    let tensor = Tensor::new(&[("input", Array::new(&[2.0], dim4!(1,1,1,1)))]);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
+    assert_eq!(Err("Target does not match the output of the Node."), network.node().read().unwrap().train(&tensor, &Array::new(&[1.0, 2.0], dim4!(1,2,1,1)), 0.1));
+}
@@ -11,6 +11,7 @@ use std::ops::Mul;
 use uuid::Uuid;
 use crate::node::attribute::{Activation, Attribute, CellType, TensorDescriptor};
 use crate::node::processor::processor::Processor;
+use crate::node::mode::Mode;
 use crate::node::network::Network;
 use crate::node::node::{Node, NodeType};
 use crate::node::scope::Scope;
@@ -37,7 +38,7 @@ fn create_weights_0_1(){
         )));
 
     let tensor = Tensor::new(&[("input", Array::new_empty(dim4!(1,2,1,1)))]);
-    network.node().read().unwrap().traverse(&tensor, true);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
 
     let w_dims = dim4!(5,5,2,5);
     let test_weights_cutouts : Array<f64> = constant(1.0, w_dims);
@@ -92,7 +93,7 @@ fn create_weights_0_2(){
         )));
 
     let tensor = Tensor::new(&[("input", Array::new_empty(dim4!(1,2,1,1)))]);
-    network.node().read().unwrap().traverse(&tensor, true);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
     let w_dims = dim4!(5,5,3,5);
     let test_weights_cutouts : Array<f64> = constant(1.0, w_dims);
 
@@ -152,7 +153,7 @@ fn create_weights_0_3(){
         )));
 
     let tensor = Tensor::new(&[("input", Array::new_empty(dim4!(1,2,1,1)))]);
-    network.node().read().unwrap().traverse(&tensor, true);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
     let w_dims = dim4!(5,5,4,5);
     let test_weights_cutouts : Array<f64> = constant(1.0, w_dims);
 
@@ -216,7 +217,7 @@ fn create_weights_1_1(){
         )));
 
     let tensor = Tensor::new(&[("input", Array::new_empty(dim4!(1,2,1,1)))]);
-    network.node().read().unwrap().traverse(&tensor, true);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
     let w_dims = dim4!(5,5,3,5);
     let test_weights_cutouts : Array<f64> = constant(1.0, w_dims);
 
@@ -283,7 +284,7 @@ fn create_weights_2_2(){
         )));
 
     let tensor = Tensor::new(&[("input", Array::new_empty(dim4!(1,2,1,1)))]);
-    network.node().read().unwrap().traverse(&tensor, true);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
     let w_dims = dim4!(5,5,5,5);
     let test_weights_cutouts : Array<f64> = constant(1.0, w_dims);
 
@@ -362,7 +363,7 @@ fn create_weights_3_3(){
         )));
 
     let tensor = Tensor::new(&[("input", Array::new_empty(dim4!(1,2,1,1)))]);
-    network.node().read().unwrap().traverse(&tensor, true);
+    network.node().read().unwrap().traverse(&tensor, true, Mode::Eval).unwrap();
     let w_dims = dim4!(5,5,7,5);
     let test_weights_cutouts : Array<f64> = constant(1.0, w_dims);
 